@@ -3,16 +3,34 @@ extern crate apiai;
 use apiai::client::{ApiAIClient,ApiRequest};
 
 
+/**
+* Usage: botcmd [config file [profile]]
+*
+* Without a config file the agent is read from APIAI_ACCESS_TOKEN (and optionally APIAI_BASE_URL
+* and APIAI_VERSION).
+*/
 fn main() {
+    use std::env;
     use std::io::{self, Write};
-    println!("API.AI Demo (Press CTRL+C to quit)...");
+    use std::process;
+
+    let args: Vec<String> = env::args().skip(1).collect();
 
-    let my_token = String::from("ce2f54f8eb444d74af85f89e30ef2fd3");
+    let client = match (args.get(0), args.get(1)) {
+        (Some(path), Some(profile)) => ApiAIClient::from_config_profile(path, profile),
+        (Some(path), None) => ApiAIClient::from_config_file(path),
+        _ => ApiAIClient::from_env()
+    };
 
-    let client = ApiAIClient{
-        access_token: my_token,
-        ..Default::default()
+    let client = match client {
+        Ok(client) => client,
+        Err(error) => {
+            println!("{}", error);
+            process::exit(1);
+        }
     };
+
+    println!("API.AI Demo (Press CTRL+C to quit)...");
     let mut input = String::new();
     loop {
         print!("<<< ");
@@ -0,0 +1,453 @@
+use serde_json::{self, Map, Value};
+use zip::{ZipArchive, ZipWriter};
+use zip::result::ZipError;
+use zip::write::FileOptions;
+
+use structure::{Entity, EntityEntry, Intent, UserSays};
+
+use std::collections::BTreeMap;
+use std::error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Seek, Write};
+use std::path::Path;
+
+
+static AGENT_FILE: &'static str = "agent.json";
+static USER_SAYS_MARKER: &'static str = "_usersays_";
+static ENTRIES_MARKER: &'static str = "_entries_";
+
+/**
+* Keys of agent.json that are read into AgentSettings fields rather than kept in `other`
+*/
+static AGENT_KEYS: [&'static str; 4] = ["description", "language", "defaultTimezone", "supportedLanguages"];
+
+/**
+* Keys the console leaves out of intent and entity files, as what they hold is kept in files of
+* its own. They are only written when they aren't empty.
+*/
+static INTENT_FILE_OMITS: [&'static str; 2] = ["userSays", "templates"];
+static ENTITY_FILE_OMITS: [&'static str; 1] = ["entries"];
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* An agent as exported from (and imported into) the api.ai console.
+*
+* The export zip holds `agent.json`, an `intents/<name>.json` file per intent with its example
+* phrases in `intents/<name>_usersays_<lang>.json`, and an `entities/<name>.json` file per entity
+* with its entries in `entities/<name>_entries_<lang>.json`. Anything else in the archive, such as
+* `package.json`, is carried through untouched.
+*
+* Files are written back pretty printed with sorted keys so that snapshots of an agent diff well.
+* Fields of agent.json, intents and entities that the crate doesn't model, such as an intent's
+* `parentId` or `lastUpdate`, are kept and written back as they were.
+*/
+#[derive(Clone)]
+pub struct AgentArchive{
+    pub agent: AgentSettings,
+    /**
+    * Intents, sorted by file name
+    */
+    pub intents: Vec<ArchivedIntent>,
+    /**
+    * Entities, sorted by file name
+    */
+    pub entities: Vec<ArchivedEntity>,
+    /**
+    * Files the archive holds that aren't agent, intent or entity files, by path
+    */
+    pub other_files: BTreeMap<String, Vec<u8>>,
+}
+
+/**
+* The agent wide settings from agent.json.
+*
+* Settings without a field of their own, such as the webhook, are kept in `other` and written
+* back as they were.
+*/
+#[derive(Clone,Serialize,Deserialize)]
+pub struct AgentSettings{
+    #[serde(default)]
+    pub description: String,
+    #[serde(default="default_language")]
+    pub language: String,
+    #[serde(rename = "defaultTimezone", skip_serializing_if="Option::is_none", default)]
+    pub default_timezone: Option<String>,
+    #[serde(rename = "supportedLanguages", default)]
+    pub supported_languages: Vec<String>,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub other: Map<String, Value>,
+}
+
+fn default_language() -> String { String::from("en") }
+
+/**
+* An intent along with its example phrases, keyed by language code
+*
+* `file_stem` is the name its files had in the archive it was read from, which the console doesn't
+* always derive from the intent name the way `write` does. It is reused when writing so that the
+* files keep their names; when None the files are named after the intent.
+*/
+#[derive(Clone)]
+pub struct ArchivedIntent{
+    pub intent: Intent,
+    pub user_says: BTreeMap<String, Vec<UserSays>>,
+    pub file_stem: Option<String>,
+}
+
+/**
+* An entity along with its entries, keyed by language code
+*
+* `file_stem` is kept and reused as for ArchivedIntent.
+*/
+#[derive(Clone)]
+pub struct ArchivedEntity{
+    pub entity: Entity,
+    pub entries: BTreeMap<String, Vec<EntityEntry>>,
+    pub file_stem: Option<String>,
+}
+
+impl AgentArchive {
+
+    /**
+    * Read an export zip
+    *
+    */
+    pub fn read<R: Read + Seek>(reader: R) -> Result<AgentArchive, ArchiveError> {
+
+        let mut zip = ZipArchive::new(reader)?;
+
+        let mut agent = Option::None;
+        let mut intents = BTreeMap::new();
+        let mut user_says = BTreeMap::new();
+        let mut entities = BTreeMap::new();
+        let mut entries = BTreeMap::new();
+        let mut other_files = BTreeMap::new();
+
+        for index in 0..zip.len() {
+            let mut file = zip.by_index(index)?;
+            let name = String::from(file.name());
+            if name.ends_with('/') {
+                continue;
+            }
+
+            let mut contents = Vec::new();
+            file.read_to_end(&mut contents)?;
+
+            match classify(&name) {
+                ArchiveFile::Agent =>
+                    agent = Option::Some(AgentSettings::from_json(&contents).map_err(|err| json_error(&name, err))?),
+                ArchiveFile::Intent(stem) => {
+                    intents.insert(stem, parse::<Intent>(&name, &contents)?);
+                },
+                ArchiveFile::UserSays(stem, lang) => {
+                    user_says.insert((stem, lang), parse::<Vec<UserSays>>(&name, &contents)?);
+                },
+                ArchiveFile::Entity(stem) => {
+                    entities.insert(stem, parse::<Entity>(&name, &contents)?);
+                },
+                ArchiveFile::Entries(stem, lang) => {
+                    entries.insert((stem, lang), parse::<Vec<EntityEntry>>(&name, &contents)?);
+                },
+                ArchiveFile::Other => {
+                    other_files.insert(name, contents);
+                }
+            }
+        }
+
+        let agent = agent.ok_or_else(|| ArchiveError::MissingFile(String::from(AGENT_FILE)))?;
+
+        let mut archived_intents: BTreeMap<String, ArchivedIntent> = intents.into_iter()
+            .map(|(stem, intent)| (stem.clone(), ArchivedIntent{ intent: intent, user_says: BTreeMap::new(), file_stem: Option::Some(stem) }))
+            .collect();
+        for ((stem, lang), phrases) in user_says {
+            match archived_intents.get_mut(&stem) {
+                Option::Some(archived) => { archived.user_says.insert(lang, phrases); },
+                Option::None => return Result::Err(ArchiveError::MissingFile(format!("intents/{}.json", stem)))
+            }
+        }
+
+        let mut archived_entities: BTreeMap<String, ArchivedEntity> = entities.into_iter()
+            .map(|(stem, entity)| (stem.clone(), ArchivedEntity{ entity: entity, entries: BTreeMap::new(), file_stem: Option::Some(stem) }))
+            .collect();
+        for ((stem, lang), values) in entries {
+            match archived_entities.get_mut(&stem) {
+                Option::Some(archived) => { archived.entries.insert(lang, values); },
+                Option::None => return Result::Err(ArchiveError::MissingFile(format!("entities/{}.json", stem)))
+            }
+        }
+
+        Result::Ok(AgentArchive{
+            agent: agent,
+            intents: archived_intents.into_iter().map(|(_, intent)| intent).collect(),
+            entities: archived_entities.into_iter().map(|(_, entity)| entity).collect(),
+            other_files: other_files,
+        })
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<AgentArchive, ArchiveError> {
+        AgentArchive::read(File::open(path)?)
+    }
+
+    /**
+    * Write the agent out as an export zip that the console can import, returning the writer.
+    *
+    * Intent and entity files keep the names they were read with. New intents and entities are
+    * named after the intent or entity, with any `/` replaced.
+    */
+    pub fn write<W: Write + Seek>(&self, writer: W) -> Result<W, ArchiveError> {
+
+        let mut zip = ZipWriter::new(writer);
+
+        let agent = self.agent.to_json().map_err(|err| json_error(AGENT_FILE, err))?;
+        add_file(&mut zip, AGENT_FILE, &agent)?;
+
+        for archived in &self.intents {
+            let stem = archived.file_stem.clone().unwrap_or_else(|| stem_for_name(&archived.intent.name));
+            add_json_omitting(&mut zip, &format!("intents/{}.json", stem), &archived.intent, &INTENT_FILE_OMITS)?;
+
+            for (lang, phrases) in &archived.user_says {
+                add_json(&mut zip, &format!("intents/{}{}{}.json", stem, USER_SAYS_MARKER, lang), phrases)?;
+            }
+        }
+
+        for archived in &self.entities {
+            let stem = archived.file_stem.clone().unwrap_or_else(|| stem_for_name(&archived.entity.name));
+            add_json_omitting(&mut zip, &format!("entities/{}.json", stem), &archived.entity, &ENTITY_FILE_OMITS)?;
+
+            for (lang, values) in &archived.entries {
+                add_json(&mut zip, &format!("entities/{}{}{}.json", stem, ENTRIES_MARKER, lang), values)?;
+            }
+        }
+
+        for (name, contents) in &self.other_files {
+            add_file(&mut zip, name, contents)?;
+        }
+
+        Result::Ok(zip.finish()?)
+    }
+
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), ArchiveError> {
+        self.write(File::create(path)?).map(|_| ())
+    }
+
+    pub fn intent(&self, name: &str) -> Option<&ArchivedIntent> {
+        self.intents.iter().find(|archived| archived.intent.name == name)
+    }
+
+    pub fn intent_mut(&mut self, name: &str) -> Option<&mut ArchivedIntent> {
+        self.intents.iter_mut().find(|archived| archived.intent.name == name)
+    }
+
+    pub fn entity(&self, name: &str) -> Option<&ArchivedEntity> {
+        self.entities.iter().find(|archived| archived.entity.name == name)
+    }
+
+    pub fn entity_mut(&mut self, name: &str) -> Option<&mut ArchivedEntity> {
+        self.entities.iter_mut().find(|archived| archived.entity.name == name)
+    }
+}
+
+impl AgentSettings {
+
+    fn from_json(contents: &[u8]) -> Result<AgentSettings, serde_json::Error> {
+        let value: Value = serde_json::from_slice(contents)?;
+        let mut settings: AgentSettings = serde_json::from_value(value.clone())?;
+
+        if let Value::Object(mut object) = value {
+            for key in AGENT_KEYS.iter() {
+                object.remove(*key);
+            }
+            settings.other = object;
+        }
+
+        Result::Ok(settings)
+    }
+
+    fn to_json(&self) -> Result<Vec<u8>, serde_json::Error> {
+        let mut object = self.other.clone();
+
+        if let Value::Object(known) = serde_json::to_value(self)? {
+            for (key, value) in known {
+                object.insert(key, value);
+            }
+        }
+
+        serde_json::to_vec_pretty(&Value::Object(object))
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* What a file in the export zip holds, along with the intent or entity (and language) it belongs to
+*/
+enum ArchiveFile {
+    Agent,
+    Intent(String),
+    UserSays(String, String),
+    Entity(String),
+    Entries(String, String),
+    Other,
+}
+
+fn classify(name: &str) -> ArchiveFile {
+
+    if name == AGENT_FILE {
+        return ArchiveFile::Agent;
+    }
+
+    let (dir, file) = match name.find('/') {
+        Option::Some(idx) => (&name[..idx], &name[idx + 1..]),
+        Option::None => return ArchiveFile::Other
+    };
+
+    if file.contains('/') || !file.ends_with(".json") {
+        return ArchiveFile::Other;
+    }
+    let stem = &file[..file.len() - ".json".len()];
+
+    match dir {
+        "intents" => match split_language(stem, USER_SAYS_MARKER) {
+            Option::Some((stem, lang)) => ArchiveFile::UserSays(stem, lang),
+            Option::None => ArchiveFile::Intent(String::from(stem))
+        },
+        "entities" => match split_language(stem, ENTRIES_MARKER) {
+            Option::Some((stem, lang)) => ArchiveFile::Entries(stem, lang),
+            Option::None => ArchiveFile::Entity(String::from(stem))
+        },
+        _ => ArchiveFile::Other
+    }
+}
+
+/**
+* Split `<name><marker><lang>` at the last marker followed by a language code, so that a name
+* which itself holds the marker isn't mistaken for a phrases or entries file
+*/
+fn split_language(stem: &str, marker: &str) -> Option<(String, String)> {
+    stem.rmatch_indices(marker)
+        .map(|(idx, _)| (&stem[..idx], &stem[idx + marker.len()..]))
+        .find(|&(_, lang)| is_language_code(lang))
+        .map(|(name, lang)| (String::from(name), String::from(lang)))
+}
+
+/**
+* Whether `code` looks like a language code such as `en`, `pt-br` or `es-419`
+*/
+fn is_language_code(code: &str) -> bool {
+    let mut parts = code.splitn(2, '-');
+    let language = parts.next().unwrap_or("");
+    if language.len() < 2 || language.len() > 3 || !language.chars().all(|c| c.is_ascii_alphabetic()) {
+        return false;
+    }
+
+    match parts.next() {
+        Option::Some(region) => region.len() >= 2 && region.len() <= 4 && region.chars().all(|c| c.is_ascii_alphanumeric()),
+        Option::None => true
+    }
+}
+
+fn stem_for_name(name: &str) -> String {
+    name.replace('/', "_")
+}
+
+fn parse<T: ::serde::Deserialize>(name: &str, contents: &[u8]) -> Result<T, ArchiveError> {
+    serde_json::from_slice(contents).map_err(|err| json_error(name, err))
+}
+
+fn add_json<W: Write + Seek, T: ::serde::Serialize>(zip: &mut ZipWriter<W>, name: &str, value: &T) -> Result<(), ArchiveError> {
+    let contents = serde_json::to_vec_pretty(value).map_err(|err| json_error(name, err))?;
+    add_file(zip, name, &contents)
+}
+
+/**
+* Write `value` as JSON, leaving out any of `omit` that are empty lists
+*/
+fn add_json_omitting<W: Write + Seek, T: ::serde::Serialize>(zip: &mut ZipWriter<W>, name: &str, value: &T, omit: &[&str]) -> Result<(), ArchiveError> {
+    let mut value = serde_json::to_value(value).map_err(|err| json_error(name, err))?;
+
+    if let Value::Object(ref mut object) = value {
+        for key in omit {
+            let empty = object.get(*key).and_then(Value::as_array).map_or(false, Vec::is_empty);
+            if empty {
+                object.remove(*key);
+            }
+        }
+    }
+
+    add_json(zip, name, &value)
+}
+
+fn add_file<W: Write + Seek>(zip: &mut ZipWriter<W>, name: &str, contents: &[u8]) -> Result<(), ArchiveError> {
+    zip.start_file(name, FileOptions::default())?;
+    zip.write_all(contents)?;
+    Result::Ok(())
+}
+
+fn json_error(name: &str, err: serde_json::Error) -> ArchiveError {
+    ArchiveError::Json{ file: String::from(name), error: err }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* Failure to read or write an agent export zip
+*
+*/
+#[derive(Debug)]
+pub enum ArchiveError{
+    Io(io::Error),
+    Zip(ZipError),
+    /**
+    * A file in the archive isn't valid JSON or doesn't match the expected structure
+    */
+    Json{ file: String, error: serde_json::Error },
+    /**
+    * The archive lacks agent.json, or has phrases or entries for an intent or entity it lacks
+    */
+    MissingFile(String),
+}
+
+impl From<io::Error> for ArchiveError {
+    fn from(err: io::Error) -> ArchiveError {
+        ArchiveError::Io(err)
+    }
+}
+
+impl From<ZipError> for ArchiveError {
+    fn from(err: ZipError) -> ArchiveError {
+        ArchiveError::Zip(err)
+    }
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ArchiveError::Io(ref err) => write!(f, "Failed to access agent archive: {}", err),
+            ArchiveError::Zip(ref err) => write!(f, "Invalid agent archive: {}", err),
+            ArchiveError::Json{ ref file, ref error } => write!(f, "Invalid {} in agent archive: {}", file, error),
+            ArchiveError::MissingFile(ref file) => write!(f, "Agent archive is missing {}", file),
+        }
+    }
+}
+
+impl error::Error for ArchiveError {
+    fn description(&self) -> &str {
+        match *self {
+            ArchiveError::Io(_) => "failed to access agent archive",
+            ArchiveError::Zip(_) => "invalid agent archive",
+            ArchiveError::Json{ .. } => "invalid JSON in agent archive",
+            ArchiveError::MissingFile(_) => "agent archive is missing a file",
+        }
+    }
+
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            ArchiveError::Io(ref err) => Option::Some(err),
+            ArchiveError::Zip(ref err) => Option::Some(err),
+            ArchiveError::Json{ ref error, .. } => Option::Some(error),
+            _ => Option::None
+        }
+    }
+}
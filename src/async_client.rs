@@ -0,0 +1,244 @@
+use futures::{future, Future, Stream};
+use futures::future::Either;
+use hyper_async;
+use hyper_async::header::ContentLength;
+use tokio_core::reactor::{Handle, Timeout};
+use serde::Serialize;
+use serde_json;
+
+use client::{ApiError, DEFAULT_BASE_URL, DEFAULT_VERSION, check_api_status};
+use structure::{ApiRequest, ApiResponse};
+use tls::{self, AsyncConnector, TlsConfig};
+use transport::{HttpTransport, HttpRequest, HttpResponse, HttpMethod, Timeouts, deadline_exceeded, map_io_to_api_err};
+
+use std::rc::Rc;
+use std::sync::Arc;
+
+
+/**
+* Future returned by an AsyncHttpTransport
+*/
+pub type TransportFuture = Box<Future<Item=HttpResponse, Error=ApiError>>;
+
+/**
+* Future returned by AsyncApiAIClient::query
+*/
+pub type QueryFuture = Box<Future<Item=ApiResponse, Error=ApiError>>;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* The non-blocking counterpart of HttpTransport.
+*
+* Takes a fully built request and returns a future that resolves to the status, headers and body
+* of the reply.
+*/
+pub trait AsyncHttpTransport {
+    fn send_async(&self, request: HttpRequest) -> TransportFuture;
+}
+
+/**
+* Async transport that calls the API over HTTPS using hyper's tokio based client and the TLS backend
+* chosen by cargo features.
+*
+* hyper's async connector has no connect or read timeouts, so only the overall request deadline
+* from the request's Timeouts is enforced, using a timer on the event loop. Proxies are not
+* supported; use the blocking HyperTransport through a BlockingTransport if one is needed.
+*/
+pub struct HyperAsyncTransport {
+    client: hyper_async::Client<AsyncConnector>,
+}
+
+impl HyperAsyncTransport {
+
+    /**
+    * Create a transport whose connections are driven by the event loop behind `handle`.
+    */
+    pub fn new(handle: &Handle) -> Result<HyperAsyncTransport, ApiError> {
+        HyperAsyncTransport::with_tls_config(handle, &TlsConfig::default())
+    }
+
+    /**
+    * Create a transport on the event loop behind `handle` that connects with the given TLS settings.
+    */
+    pub fn with_tls_config(handle: &Handle, tls: &TlsConfig) -> Result<HyperAsyncTransport, ApiError> {
+        let connector = tls::async_connector(tls, handle).map_err(ApiError::TlsInit)?;
+
+        Result::Ok(HyperAsyncTransport {
+            client: hyper_async::Client::configure().connector(connector).build(handle)
+        })
+    }
+}
+
+impl AsyncHttpTransport for HyperAsyncTransport {
+
+    fn send_async(&self, request: HttpRequest) -> TransportFuture {
+
+        let uri = match request.url.parse::<hyper_async::Uri>() {
+            Result::Ok(uri) => uri,
+            Result::Err(err) => return Box::new(future::err(ApiError::HttpError(err.to_string())))
+        };
+
+        let method = match request.method {
+            HttpMethod::Get => hyper_async::Method::Get,
+            HttpMethod::Post => hyper_async::Method::Post,
+            HttpMethod::Put => hyper_async::Method::Put,
+            HttpMethod::Delete => hyper_async::Method::Delete,
+        };
+
+        let deadline = request.timeouts.request;
+
+        let mut hyper_request = hyper_async::Request::new(method, uri);
+        for (name, value) in request.headers {
+            hyper_request.headers_mut().append_raw(name, value.into_bytes());
+        }
+        hyper_request.headers_mut().set(ContentLength(request.body.len() as u64));
+        hyper_request.set_body(request.body);
+
+        let response = self.client.request(hyper_request)
+            .and_then(|response| {
+                let status = response.status().as_u16();
+                let headers = response.headers().iter()
+                    .map(|header| (String::from(header.name()), header.value_string()))
+                    .collect();
+
+                response.body().concat2().map(move |body| HttpResponse {
+                    status: status,
+                    headers: headers,
+                    body: body.to_vec(),
+                })
+            })
+            .map_err(map_hyper_to_api_err);
+
+        let deadline = match deadline {
+            Option::Some(deadline) => deadline,
+            Option::None => return Box::new(response)
+        };
+
+        let timeout = match Timeout::new(deadline, self.client.handle()) {
+            Result::Ok(timeout) => timeout,
+            Result::Err(err) => return Box::new(future::err(map_io_to_api_err(err)))
+        };
+
+        Box::new(response.select2(timeout).then(move |result| match result {
+            Result::Ok(Either::A((response, _))) => Result::Ok(response),
+            Result::Ok(Either::B(_)) => Result::Err(deadline_exceeded(deadline)),
+            Result::Err(Either::A((err, _))) => Result::Err(err),
+            Result::Err(Either::B((err, _))) => Result::Err(map_io_to_api_err(err)),
+        }))
+    }
+}
+
+fn map_hyper_to_api_err(err : hyper_async::Error) -> ApiError {
+    match err {
+        hyper_async::Error::Io(err) => map_io_to_api_err(err),
+        err => ApiError::HttpError(err.to_string())
+    }
+}
+
+/**
+* Adapts a blocking HttpTransport for use with AsyncApiAIClient.
+*
+* The request is sent on the calling thread and the returned future is already resolved.
+*/
+pub struct BlockingTransport(pub Arc<HttpTransport>);
+
+impl AsyncHttpTransport for BlockingTransport {
+
+    fn send_async(&self, request: HttpRequest) -> TransportFuture {
+        Box::new(future::result(self.0.send(request)))
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* AsyncApiAIClient calls the API.ai REST services without blocking the calling thread.
+*
+* Queries return futures that should be driven by a tokio event loop. The blocking ApiAIClient is a
+* thin wrapper around this client.
+*
+* The `timeouts` are handed to the transport with every request.
+*/
+#[derive(Clone)]
+pub struct AsyncApiAIClient{
+    pub version: String,
+    pub access_token: String,
+    pub base_url: String,
+    pub transport: Rc<AsyncHttpTransport>,
+    pub timeouts: Timeouts,
+}
+
+impl AsyncApiAIClient {
+
+    /**
+    * Create a client using the default base url and version that sends requests using
+    * HyperAsyncTransport on the given event loop.
+    */
+    pub fn new(handle: &Handle, access_token: String) -> Result<AsyncApiAIClient, ApiError> {
+        let transport = HyperAsyncTransport::new(handle)?;
+
+        Result::Ok(AsyncApiAIClient{
+            version: String::from(DEFAULT_VERSION),
+            access_token: access_token,
+            base_url: String::from(DEFAULT_BASE_URL),
+            transport: Rc::new(transport),
+            timeouts: Timeouts::default()
+        })
+    }
+
+    /**
+    * Carry out an API.ai query
+    *
+    */
+    pub fn query(&self, query: ApiRequest) -> QueryFuture {
+        match serialize_api_request(&query) {
+            Result::Ok(body) => self.query_body(body),
+            Result::Err(err) => Box::new(future::err(err))
+        }
+    }
+
+    /**
+    * Send a query that has already been serialized
+    */
+    pub(crate) fn query_body(&self, body: Vec<u8>) -> QueryFuture {
+
+        let request = HttpRequest {
+            method: HttpMethod::Post,
+            url: self.base_url.clone() + "/query?v=" + self.version.as_str(),
+            headers: vec![
+                (String::from("Authorization"), format!("Bearer {}", self.access_token)),
+                (String::from("Content-Type"), String::from("application/json")),
+            ],
+            body: body,
+            timeouts: self.timeouts.clone(),
+        };
+
+        Box::new(self.transport.send_async(request)
+            .and_then(deserialize_api_response))
+    }
+}
+
+/**
+* Serialize a request body, keeping the serde error if it fails.
+*
+*/
+pub(crate) fn serialize_api_request<T: Serialize>(request: &T) -> Result<Vec<u8>, ApiError>{
+    serde_json::to_vec(request).map_err(ApiError::RequestSerialization)
+}
+
+/**
+* Check the status of a query response and parse its body.
+*
+*/
+pub(crate) fn deserialize_api_response(response : HttpResponse) -> Result<ApiResponse, ApiError>{
+    check_api_status(response).and_then(|response| {
+        serde_json::from_slice(&response.body).map_err(map_serde_to_api_err)
+    })
+}
+
+
+fn map_serde_to_api_err(err : serde_json::Error) -> ApiError {
+
+    ApiError::SerializationError(err)
+}
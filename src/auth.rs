@@ -0,0 +1,410 @@
+use base64;
+use ring::rand::SystemRandom;
+use ring::signature::{RSAKeyPair, RSASigningState, RSA_PKCS1_SHA256};
+use serde_json;
+use untrusted;
+
+use client::{ApiError, is_success, parse_body, status_error};
+use transport::{HttpTransport, HttpRequest, HttpResponse, HttpMethod, HyperTransport, Timeouts};
+
+use std::cmp;
+use std::error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::{Arc, Mutex, TryLockError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+
+pub static CLOUD_PLATFORM_SCOPE: &'static str = "https://www.googleapis.com/auth/cloud-platform";
+static JWT_BEARER_GRANT: &'static str = "urn:ietf:params:oauth:grant-type:jwt-bearer";
+
+/**
+* How long the signed assertion is valid for, and the longest a fetched token is trusted for
+*/
+const ASSERTION_LIFETIME_SECS: u64 = 3600;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* Supplies the bearer token sent with each request.
+*
+* This is only used by the Dialogflow v2 client, `v2::DialogflowClient`. The v1 API that
+* ApiAIClient calls takes an agent's client access token rather than an OAuth2 token, so
+* ApiAIClient keeps its fixed `access_token` and doesn't take a provider.
+*
+* Providers are shared between clones of a client, so they must be safe to call from several
+* threads at once.
+*/
+pub trait AuthProvider: Send + Sync {
+
+    /**
+    * The token to send with the next request, fetching a new one first if need be
+    */
+    fn access_token(&self) -> Result<String, ApiError>;
+}
+
+/**
+* A fixed token, such as one from `gcloud auth print-access-token`
+*
+*/
+#[derive(Clone,Debug)]
+pub struct StaticToken(pub String);
+
+impl AuthProvider for StaticToken {
+    fn access_token(&self) -> Result<String, ApiError> {
+        Result::Ok(self.0.clone())
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* The JSON key file downloaded for a Google Cloud service account
+*
+*/
+#[derive(Clone,Deserialize)]
+pub struct ServiceAccountKey {
+    pub project_id: String,
+    pub private_key_id: String,
+    /**
+    * PEM encoded PKCS#8 RSA private key
+    */
+    pub private_key: String,
+    pub client_email: String,
+    pub token_uri: String,
+}
+
+impl ServiceAccountKey {
+
+    pub fn parse(json: &str) -> Result<ServiceAccountKey, AuthError> {
+        serde_json::from_str(json).map_err(|err| AuthError::InvalidKey(err.to_string()))
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<ServiceAccountKey, AuthError> {
+        let mut contents = String::new();
+        File::open(path)?.read_to_string(&mut contents)?;
+
+        ServiceAccountKey::parse(&contents)
+    }
+}
+
+/**
+* Gets OAuth2 access tokens for a service account by signing a JWT with its private key (RS256)
+* and exchanging it at the key's token endpoint.
+*
+* Tokens are cached and fetched again once they are within `refresh_margin` (five minutes by
+* default) of expiring, so a request never goes out with a token that is about to lapse. Only one
+* thread fetches a token at a time; while it does, other threads keep using the cached token if it
+* hasn't expired yet. If the fetch fails before the cached token has expired, that token is used.
+*/
+pub struct ServiceAccountProvider {
+    key: ServiceAccountKey,
+    key_pair: Arc<RSAKeyPair>,
+    scopes: Vec<String>,
+    token_uri: String,
+    transport: Arc<HttpTransport>,
+    timeouts: Timeouts,
+    refresh_margin: Duration,
+    cached: Mutex<Option<CachedToken>>,
+    refreshing: Mutex<()>,
+}
+
+#[derive(Clone)]
+struct CachedToken {
+    token: String,
+    expires: Instant,
+}
+
+#[derive(Serialize)]
+struct JwtHeader<'a> {
+    alg: &'a str,
+    typ: &'a str,
+    kid: &'a str,
+}
+
+#[derive(Serialize)]
+struct JwtClaims<'a> {
+    iss: &'a str,
+    scope: String,
+    aud: &'a str,
+    iat: u64,
+    exp: u64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+/**
+* The body of a failed token request
+*/
+#[derive(Deserialize)]
+struct TokenError {
+    error: String,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+impl ServiceAccountProvider {
+
+    /**
+    * Create a provider for the cloud-platform scope that fetches tokens over a HyperTransport.
+    */
+    pub fn new(key: ServiceAccountKey) -> Result<ServiceAccountProvider, AuthError> {
+
+        let der = pem_to_der(&key.private_key)?;
+        let key_pair = RSAKeyPair::from_pkcs8(untrusted::Input::from(&der))
+            .map_err(|_| AuthError::InvalidKey(String::from("private_key is not a PKCS#8 RSA key")))?;
+
+        Result::Ok(ServiceAccountProvider{
+            token_uri: key.token_uri.clone(),
+            key: key,
+            key_pair: Arc::new(key_pair),
+            scopes: vec![String::from(CLOUD_PLATFORM_SCOPE)],
+            transport: Arc::new(HyperTransport::new()),
+            timeouts: Timeouts::default(),
+            refresh_margin: Duration::from_secs(300),
+            cached: Mutex::new(Option::None),
+            refreshing: Mutex::new(()),
+        })
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<ServiceAccountProvider, AuthError> {
+        ServiceAccountProvider::new(ServiceAccountKey::from_file(path)?)
+    }
+
+    pub fn key(&self) -> &ServiceAccountKey {
+        &self.key
+    }
+
+    pub fn with_scopes(mut self, scopes: Vec<String>) -> ServiceAccountProvider {
+        self.scopes = scopes;
+        self
+    }
+
+    /**
+    * Exchange assertions at this endpoint instead of the key's `token_uri`
+    */
+    pub fn with_token_uri(mut self, token_uri: &str) -> ServiceAccountProvider {
+        self.token_uri = String::from(token_uri);
+        self
+    }
+
+    pub fn with_transport(mut self, transport: Arc<HttpTransport>) -> ServiceAccountProvider {
+        self.transport = transport;
+        self
+    }
+
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> ServiceAccountProvider {
+        self.timeouts = timeouts;
+        self
+    }
+
+    pub fn with_refresh_margin(mut self, margin: Duration) -> ServiceAccountProvider {
+        self.refresh_margin = margin;
+        self
+    }
+
+    /**
+    * Build and sign the JWT that is exchanged for an access token
+    *
+    */
+    pub fn assertion(&self) -> Result<String, ApiError> {
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or(0);
+
+        let header = JwtHeader{ alg: "RS256", typ: "JWT", kid: &self.key.private_key_id };
+        let claims = JwtClaims{
+            iss: &self.key.client_email,
+            scope: self.scopes.join(" "),
+            aud: &self.token_uri,
+            iat: now,
+            exp: now + ASSERTION_LIFETIME_SECS,
+        };
+
+        let header = serde_json::to_vec(&header).map_err(ApiError::RequestSerialization)?;
+        let claims = serde_json::to_vec(&claims).map_err(ApiError::RequestSerialization)?;
+        let signing_input = format!("{}.{}", base64_url(&header), base64_url(&claims));
+
+        let mut signature = vec![0; self.key_pair.public_modulus_len()];
+        RSASigningState::new(self.key_pair.clone())
+            .and_then(|mut state| state.sign(&RSA_PKCS1_SHA256, &SystemRandom::new(), signing_input.as_bytes(), &mut signature))
+            .map_err(|_| ApiError::Auth(AuthError::Signing))?;
+
+        Result::Ok(format!("{}.{}", signing_input, base64_url(&signature)))
+    }
+
+    fn cached_token(&self) -> Option<CachedToken> {
+        self.cached.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+
+    fn needs_refresh(&self, token: &CachedToken) -> bool {
+        Instant::now().checked_add(self.refresh_margin).map_or(true, |refresh| refresh >= token.expires)
+    }
+
+    fn fetch_token(&self) -> Result<CachedToken, ApiError> {
+
+        let requested = Instant::now();
+        let body = format!("grant_type={}&assertion={}", form_encode(JWT_BEARER_GRANT), form_encode(&self.assertion()?));
+
+        let request = HttpRequest{
+            method: HttpMethod::Post,
+            url: self.token_uri.clone(),
+            headers: vec![(String::from("Content-Type"), String::from("application/x-www-form-urlencoded"))],
+            body: body.into_bytes(),
+            timeouts: self.timeouts.clone(),
+        };
+
+        let response: TokenResponse = self.transport.send(request)
+            .and_then(check_token_status)
+            .and_then(parse_body)?;
+
+        // Google issues tokens for an hour, so anything longer is treated as an hour
+        let expires_in = Duration::from_secs(cmp::min(response.expires_in, ASSERTION_LIFETIME_SECS));
+
+        Result::Ok(CachedToken{
+            token: response.access_token,
+            expires: requested + expires_in,
+        })
+    }
+}
+
+impl AuthProvider for ServiceAccountProvider {
+
+    fn access_token(&self) -> Result<String, ApiError> {
+
+        let current = self.cached_token();
+        if let Option::Some(ref token) = current {
+            if !self.needs_refresh(token) {
+                return Result::Ok(token.token.clone());
+            }
+        }
+
+        // the cache lock isn't held while fetching, this one keeps it to a single fetch at a time
+        let _refreshing = match self.refreshing.try_lock() {
+            Result::Ok(guard) => guard,
+            Result::Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+            Result::Err(TryLockError::WouldBlock) => match current {
+                Option::Some(ref token) if Instant::now() < token.expires => return Result::Ok(token.token.clone()),
+                _ => self.refreshing.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+            }
+        };
+
+        // another thread may have fetched a token while this one waited
+        let current = self.cached_token();
+        if let Option::Some(ref token) = current {
+            if !self.needs_refresh(token) {
+                return Result::Ok(token.token.clone());
+            }
+        }
+
+        match self.fetch_token() {
+            Result::Ok(token) => {
+                let access_token = token.token.clone();
+                *self.cached.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Option::Some(token);
+                Result::Ok(access_token)
+            },
+            Result::Err(err) => match current {
+                Option::Some(ref token) if Instant::now() < token.expires => Result::Ok(token.token.clone()),
+                _ => Result::Err(err)
+            }
+        }
+    }
+}
+
+fn check_token_status(response: HttpResponse) -> Result<HttpResponse, ApiError> {
+
+    if is_success(response.status) {
+        return Result::Ok(response);
+    }
+
+    let (error_type, error_details) = match serde_json::from_slice::<TokenError>(&response.body) {
+        Result::Ok(error) => (error.error, error.error_description),
+        Result::Err(_) => (String::new(), Option::None)
+    };
+
+    Result::Err(status_error(&response, response.status, error_type, error_details))
+}
+
+fn pem_to_der(pem: &str) -> Result<Vec<u8>, AuthError> {
+    let body: String = pem.lines()
+        .filter(|line| !line.starts_with("-----"))
+        .flat_map(|line| line.chars())
+        .filter(|c| !c.is_whitespace())
+        .collect();
+
+    if body.is_empty() {
+        return Result::Err(AuthError::InvalidKey(String::from("private_key holds no PEM data")));
+    }
+
+    base64::decode(&body).map_err(|err| AuthError::InvalidKey(format!("private_key is not valid PEM: {}", err)))
+}
+
+fn base64_url(data: &[u8]) -> String {
+    base64::encode_config(data, base64::URL_SAFE_NO_PAD)
+}
+
+/**
+* Percent encode a value for an application/x-www-form-urlencoded body
+*/
+fn form_encode(value: &str) -> String {
+    value.bytes()
+        .map(|byte| match byte {
+            b'A'...b'Z' | b'a'...b'z' | b'0'...b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte)
+        })
+        .collect()
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* Failure to load service account credentials or sign a token request
+*
+*/
+#[derive(Debug)]
+pub enum AuthError {
+    Io(io::Error),
+    /**
+    * The key file isn't a service account key or its private key can't be used
+    */
+    InvalidKey(String),
+    Signing,
+}
+
+impl From<io::Error> for AuthError {
+    fn from(err: io::Error) -> AuthError {
+        AuthError::Io(err)
+    }
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AuthError::Io(ref err) => write!(f, "Failed to read service account key: {}", err),
+            AuthError::InvalidKey(ref msg) => write!(f, "Invalid service account key: {}", msg),
+            AuthError::Signing => write!(f, "Failed to sign token request"),
+        }
+    }
+}
+
+impl error::Error for AuthError {
+    fn description(&self) -> &str {
+        match *self {
+            AuthError::Io(_) => "failed to read service account key",
+            AuthError::InvalidKey(_) => "invalid service account key",
+            AuthError::Signing => "failed to sign token request",
+        }
+    }
+
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            AuthError::Io(ref err) => Option::Some(err),
+            _ => Option::None
+        }
+    }
+}
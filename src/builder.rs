@@ -0,0 +1,389 @@
+use chrono::NaiveDate;
+use hyper::Url;
+
+use client::{ApiAIClient, DEFAULT_BASE_URL, DEFAULT_VERSION};
+use proxy::ProxyConfig;
+use rate_limit::RateLimiter;
+use retry::RetryPolicy;
+use tls::{self, TlsConfig};
+use transport::{HttpTransport, HyperTransport, PoolConfig, ProxyMode, Timeouts, TransportSettings};
+
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* Builds an ApiAIClient, checking the configuration before handing it over.
+*
+* Settings that aren't given fall back to the same defaults as `ApiAIClient::default()`. `build()`
+* checks everything at once so that a misconfigured client reports every problem in one go rather
+* than one per attempt.
+*
+* The pool, proxy and TLS settings configure the default HyperTransport, so they can't be combined
+* with a custom transport. Without proxy settings HTTPS_PROXY and NO_PROXY are followed.
+*/
+#[derive(Clone)]
+pub struct ApiAIClientBuilder {
+    access_token: Option<String>,
+    base_url: String,
+    version: String,
+    transport: Option<Arc<HttpTransport>>,
+    pool_config: Option<PoolConfig>,
+    proxy: ProxySetting,
+    proxy_credentials: Option<(String, String)>,
+    no_proxy: Option<Vec<String>>,
+    tls_config: Option<TlsConfig>,
+    retry_policy: RetryPolicy,
+    timeouts: Timeouts,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+#[derive(Clone,PartialEq)]
+enum ProxySetting {
+    FromEnv,
+    Direct,
+    Url(String),
+}
+
+impl Default for ApiAIClientBuilder {
+    fn default() -> ApiAIClientBuilder {
+        ApiAIClientBuilder {
+            access_token: Option::None,
+            base_url: String::from(DEFAULT_BASE_URL),
+            version: String::from(DEFAULT_VERSION),
+            transport: Option::None,
+            pool_config: Option::None,
+            proxy: ProxySetting::FromEnv,
+            proxy_credentials: Option::None,
+            no_proxy: Option::None,
+            tls_config: Option::None,
+            retry_policy: RetryPolicy::default(),
+            timeouts: Timeouts::default(),
+            rate_limiter: Option::None,
+        }
+    }
+}
+
+impl ApiAIClientBuilder {
+
+    pub fn new() -> ApiAIClientBuilder {
+        ApiAIClientBuilder::default()
+    }
+
+    /**
+    * The agent's client or developer access token (32 hexadecimal characters)
+    */
+    pub fn access_token<S: Into<String>>(mut self, access_token: S) -> ApiAIClientBuilder {
+        self.access_token = Option::Some(access_token.into());
+        self
+    }
+
+    /**
+    * Base url of the API. Trailing slashes are removed.
+    */
+    pub fn base_url<S: Into<String>>(mut self, base_url: S) -> ApiAIClientBuilder {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /**
+    * API version as a YYYYMMDD date e.g. `20150910`
+    */
+    pub fn version<S: Into<String>>(mut self, version: S) -> ApiAIClientBuilder {
+        self.version = version.into();
+        self
+    }
+
+    pub fn transport(mut self, transport: Arc<HttpTransport>) -> ApiAIClientBuilder {
+        self.transport = Option::Some(transport);
+        self
+    }
+
+    pub fn pool_config(mut self, pool_config: PoolConfig) -> ApiAIClientBuilder {
+        self.pool_config = Option::Some(pool_config);
+        self
+    }
+
+    /**
+    * Tunnel connections through an HTTP proxy e.g. `http://proxy.example.com:3128`
+    */
+    pub fn proxy<S: Into<String>>(mut self, url: S) -> ApiAIClientBuilder {
+        self.proxy = ProxySetting::Url(url.into());
+        self
+    }
+
+    /**
+    * Basic auth credentials for the proxy, overriding any given in its url
+    */
+    pub fn proxy_credentials(mut self, username: &str, password: &str) -> ApiAIClientBuilder {
+        self.proxy_credentials = Option::Some((String::from(username), String::from(password)));
+        self
+    }
+
+    /**
+    * Hosts to connect to directly, overriding NO_PROXY
+    */
+    pub fn no_proxy(mut self, hosts: Vec<String>) -> ApiAIClientBuilder {
+        self.no_proxy = Option::Some(hosts);
+        self
+    }
+
+    /**
+    * Always connect directly, ignoring HTTPS_PROXY
+    */
+    pub fn without_proxy(mut self) -> ApiAIClientBuilder {
+        self.proxy = ProxySetting::Direct;
+        self
+    }
+
+    /**
+    * Extra root certificates, a client certificate or a minimum protocol version
+    */
+    pub fn tls_config(mut self, tls_config: TlsConfig) -> ApiAIClientBuilder {
+        self.tls_config = Option::Some(tls_config);
+        self
+    }
+
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> ApiAIClientBuilder {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn timeouts(mut self, timeouts: Timeouts) -> ApiAIClientBuilder {
+        self.timeouts = timeouts;
+        self
+    }
+
+    pub fn rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> ApiAIClientBuilder {
+        self.rate_limiter = Option::Some(rate_limiter);
+        self
+    }
+
+    /**
+    * Check the configuration and create the client, or return every problem that was found.
+    *
+    */
+    pub fn build(self) -> Result<ApiAIClient, BuildError> {
+
+        let mut problems = Vec::new();
+
+        match self.access_token {
+            Option::None => problems.push(ConfigProblem::MissingAccessToken),
+            Option::Some(ref token) if !is_valid_token(token) => problems.push(ConfigProblem::InvalidAccessToken),
+            _ => ()
+        }
+
+        let base_url = match normalize_base_url(&self.base_url) {
+            Result::Ok(base_url) => base_url,
+            Result::Err(reason) => {
+                problems.push(ConfigProblem::InvalidBaseUrl{ url: self.base_url.clone(), reason: reason });
+                String::new()
+            }
+        };
+
+        if self.version.len() != 8 || NaiveDate::parse_from_str(&self.version, "%Y%m%d").is_err() {
+            problems.push(ConfigProblem::InvalidVersion(self.version.clone()));
+        }
+
+        if is_zero(self.timeouts.connect) {
+            problems.push(ConfigProblem::ZeroTimeout("connect"));
+        }
+        if is_zero(self.timeouts.read) {
+            problems.push(ConfigProblem::ZeroTimeout("read"));
+        }
+        if is_zero(self.timeouts.request) {
+            problems.push(ConfigProblem::ZeroTimeout("request"));
+        }
+
+        if self.retry_policy.max_attempts == 0 {
+            problems.push(ConfigProblem::NoAttempts);
+        }
+
+        let proxy = match self.proxy {
+            ProxySetting::FromEnv => match ProxyConfig::from_env() {
+                Result::Ok(proxy) => proxy,
+                Result::Err(problem) => {
+                    problems.push(problem);
+                    Option::None
+                }
+            },
+            ProxySetting::Direct => Option::None,
+            ProxySetting::Url(ref url) => match ProxyConfig::new(url) {
+                Result::Ok(proxy) => Option::Some(proxy),
+                Result::Err(problem) => {
+                    problems.push(problem);
+                    Option::None
+                }
+            }
+        };
+
+        let proxy = proxy.map(|proxy| {
+            let proxy = match self.proxy_credentials {
+                Option::Some((ref username, ref password)) => proxy.with_credentials(username, password),
+                Option::None => proxy
+            };
+            match self.no_proxy {
+                Option::Some(ref hosts) => proxy.with_no_proxy(hosts.clone()),
+                Option::None => proxy
+            }
+        });
+
+        // build the TLS backend now so that bad certificates are reported here rather than on the
+        // first query
+        if let Option::Some(ref tls_config) = self.tls_config {
+            if let Result::Err(err) = tls::ssl_client(tls_config) {
+                problems.push(ConfigProblem::InvalidTls(err.to_string()));
+            }
+        }
+
+        let transport_settings = self.pool_config.is_some() || self.proxy != ProxySetting::FromEnv ||
+            self.proxy_credentials.is_some() || self.no_proxy.is_some() || self.tls_config.is_some();
+        if self.transport.is_some() && transport_settings {
+            problems.push(ConfigProblem::SettingsWithCustomTransport);
+        }
+
+        if !problems.is_empty() {
+            return Result::Err(BuildError{ problems: problems });
+        }
+
+        let transport_settings = TransportSettings{
+            pool: self.pool_config.unwrap_or_default(),
+            proxy: match proxy {
+                Option::Some(proxy) => ProxyMode::Via(proxy),
+                Option::None => ProxyMode::Direct
+            },
+            tls: self.tls_config.unwrap_or_default(),
+        };
+        let transport = match self.transport {
+            Option::Some(transport) => transport,
+            Option::None => Arc::new(HyperTransport::from_settings(transport_settings.clone()))
+        };
+
+        Result::Ok(ApiAIClient{
+            version: self.version,
+            access_token: self.access_token.unwrap_or_default(),
+            base_url: base_url,
+            transport: transport,
+            retry_policy: self.retry_policy,
+            timeouts: self.timeouts,
+            rate_limiter: self.rate_limiter,
+            transport_settings: transport_settings,
+        })
+    }
+}
+
+fn is_valid_token(token: &str) -> bool {
+    token.len() == 32 && token.chars().all(|c| c.is_digit(16))
+}
+
+fn is_zero(timeout: Option<Duration>) -> bool {
+    timeout == Option::Some(Duration::from_secs(0))
+}
+
+/**
+* Parse a base url, requiring an http(s) url with a host and no query or fragment, and strip any
+* trailing slashes so that paths can be appended to it.
+*/
+pub(crate) fn normalize_base_url(base_url: &str) -> Result<String, String> {
+
+    let url = Url::parse(base_url.trim()).map_err(|err| err.to_string())?;
+
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Result::Err(format!("unsupported scheme '{}'", url.scheme()));
+    }
+    if url.host_str().map_or(true, |host| host.is_empty()) {
+        return Result::Err(String::from("missing host"));
+    }
+    if url.query().is_some() || url.fragment().is_some() {
+        return Result::Err(String::from("must not have a query or fragment"));
+    }
+
+    Result::Ok(String::from(url.as_str().trim_right_matches('/')))
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* A single problem with the configuration given to ApiAIClientBuilder
+*
+*/
+#[derive(Clone,Debug,PartialEq)]
+pub enum ConfigProblem {
+    MissingAccessToken,
+    /**
+    * The token is not 32 hexadecimal characters. The token itself is left out so it isn't logged.
+    */
+    InvalidAccessToken,
+    InvalidBaseUrl{ url: String, reason: String },
+    InvalidVersion(String),
+    InvalidProxy{ url: String, reason: String },
+    /**
+    * The TLS backend rejected the certificates or client identity
+    */
+    InvalidTls(String),
+    /**
+    * Pool, proxy or TLS settings were given along with a custom transport, which wouldn't use them
+    */
+    SettingsWithCustomTransport,
+    /**
+    * A timeout of zero was given; use `None` to wait indefinitely
+    */
+    ZeroTimeout(&'static str),
+    /**
+    * The retry policy allows no attempts at all
+    */
+    NoAttempts,
+    /**
+    * A rate limit of zero requests or over a zero period was given
+    */
+    ZeroRateLimit,
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConfigProblem::MissingAccessToken => write!(f, "no access token was given"),
+            ConfigProblem::InvalidAccessToken => write!(f, "access token should be 32 hexadecimal characters"),
+            ConfigProblem::InvalidBaseUrl{ ref url, ref reason } => write!(f, "invalid base url '{}': {}", url, reason),
+            ConfigProblem::InvalidVersion(ref version) => write!(f, "version '{}' is not a YYYYMMDD date", version),
+            ConfigProblem::InvalidProxy{ ref url, ref reason } => write!(f, "invalid proxy url '{}': {}", url, reason),
+            ConfigProblem::InvalidTls(ref reason) => write!(f, "invalid TLS settings: {}", reason),
+            ConfigProblem::SettingsWithCustomTransport => write!(f, "pool, proxy and TLS settings can't be used with a custom transport"),
+            ConfigProblem::ZeroTimeout(name) => write!(f, "{} timeout must be greater than zero", name),
+            ConfigProblem::NoAttempts => write!(f, "retry policy must allow at least one attempt"),
+            ConfigProblem::ZeroRateLimit => write!(f, "rate limit must allow at least one request over a non-zero period"),
+        }
+    }
+}
+
+/**
+* Returned by ApiAIClientBuilder::build listing everything wrong with the configuration
+*
+*/
+#[derive(Clone,Debug,PartialEq)]
+pub struct BuildError {
+    pub problems: Vec<ConfigProblem>,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid API.ai client configuration: ")?;
+        for (i, problem) in self.problems.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", problem)?;
+        }
+        Result::Ok(())
+    }
+}
+
+impl error::Error for BuildError {
+    fn description(&self) -> &str {
+        "invalid API.ai client configuration"
+    }
+}
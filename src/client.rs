@@ -2,22 +2,74 @@
 pub use structure::{
         ApiRequest,
         ApiEvent,
+        ApiLocation,
+        ApiOriginalRequest,
         ApiResponse,
         ApiStatus,
         ApiResult,
         ApiMetadata,
         ApiMessage,
+        TextResponse,
+        Card,
+        CardButton,
+        QuickReplies,
+        ImageResponse,
+        CustomPayload,
+        SimpleResponse,
+        BasicCard,
+        GoogleImage,
+        GoogleButton,
+        OpenUrlAction,
+        SuggestionChips,
+        Suggestion,
+        LinkOutChip,
+        ListCard,
+        CarouselCard,
+        SelectItem,
+        OptionInfo,
         ApiFulfillment,
+        ApiContext,
+        Entity,
+        EntityEntry,
+        EntitySummary,
+        Intent,
+        IntentEvent,
+        IntentParameter,
+        IntentResponse,
+        IntentSummary,
+        UserSays,
+        UserSaysPart,
+        UserEntity,
 };
+pub use parameters::Parameters;
 
+use serde::{Deserialize, Serialize};
 use serde_json;
-use hyper::client::{Client, Response};
-use hyper::header::{Authorization, Bearer, ContentType};
-use hyper::net::HttpsConnector;
-use hyper_native_tls::NativeTlsClient;
+use futures::Future;
+use hyper::Url;
+use hyper::header::HttpDate;
+use async_client::{AsyncApiAIClient, BlockingTransport, serialize_api_request, deserialize_api_response};
+use transport::{HttpTransport, HttpRequest, HttpResponse, HttpMethod, HyperTransport, PoolConfig, ProxyMode, Timeouts, TransportSettings,
+                deadline_exceeded};
+use proxy::ProxyConfig;
+use tls::{TlsConfig, TlsError};
+use auth::AuthError;
+use voice::{AudioSource, AudioContentType, multipart_body};
 
-use std;
-use std::io::Read;
+use builder::ApiAIClientBuilder;
+use config::{ConfigError, ConfigFile, profile_from_env};
+use retry::RetryPolicy;
+use rate_limit::RateLimiter;
+
+use std::cmp;
+use std::error;
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
@@ -27,13 +79,40 @@ use std::io::Read;
 *
 * APIAIClient calls the API.AI REST service using hyper. The API version and base url can be
 * configured and an access token for calling the API must be provided when creating the struct.
+* Use `ApiAIClient::builder()` to have the token, base url and version checked up front.
+*
+* Requests are put on the wire by an HttpTransport, which defaults to HyperTransport. Swap in a
+* MemoryTransport to exercise the client without a network.
+*
+* The client is cheap to clone and safe to share between threads; clones share the same transport
+* and therefore the same connection pool.
 *
+* Transient failures are retried according to `retry_policy`, which by default makes a single
+* attempt. `timeouts` bounds how long a query may take; the overall `request` deadline covers every
+* attempt including the time spent backing off between them.
+*
+* An optional `rate_limiter` is consulted before every attempt. It lives in an Arc so that clones of
+* the client share the same allowance.
+*
+* `transport_settings` are the pool, proxy and TLS settings the HyperTransport was built from.
+* Each of `set_pool_config`, `set_proxy` and `set_tls_config` changes one of them and rebuilds the
+* transport from all three.
 */
-#[derive(Serialize,Deserialize)]
+#[derive(Clone,Serialize,Deserialize)]
 pub struct ApiAIClient{
     pub version: String,
     pub access_token: String,
     pub base_url: String,
+    #[serde(skip_serializing, skip_deserializing, default="default_transport")]
+    pub transport: Arc<HttpTransport>,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub retry_policy: RetryPolicy,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub timeouts: Timeouts,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub transport_settings: TransportSettings,
 }
 
 
@@ -46,65 +125,519 @@ impl Default for ApiAIClient{
         ApiAIClient{
             access_token: String::new(),
             version: String::from(DEFAULT_VERSION),
-            base_url: String::from(DEFAULT_BASE_URL)
+            base_url: String::from(DEFAULT_BASE_URL),
+            transport: default_transport(),
+            retry_policy: RetryPolicy::default(),
+            timeouts: Timeouts::default(),
+            rate_limiter: Option::None,
+            transport_settings: TransportSettings::default(),
         }
     }
 }
 
+fn default_transport() -> Arc<HttpTransport> {
+    Arc::new(HyperTransport::new())
+}
+
 impl ApiAIClient{
 
+    /**
+    * Start building a client whose settings are checked before it is created.
+    *
+    */
+    pub fn builder() -> ApiAIClientBuilder {
+        ApiAIClientBuilder::new()
+    }
+
+    /**
+    * Create a client from the APIAI_ACCESS_TOKEN, APIAI_BASE_URL and APIAI_VERSION environment
+    * variables. Only the access token is required.
+    *
+    */
+    pub fn from_env() -> Result<ApiAIClient, ConfigError> {
+        Result::Ok(profile_from_env().builder().build()?)
+    }
+
+    /**
+    * Create a client from the default agent profile in a TOML or JSON configuration file.
+    *
+    */
+    pub fn from_config_file<P: AsRef<Path>>(path: P) -> Result<ApiAIClient, ConfigError> {
+        Result::Ok(ConfigFile::load(path)?.profile(Option::None)?.builder().build()?)
+    }
+
+    /**
+    * Create a client from the named agent profile in a TOML or JSON configuration file.
+    *
+    */
+    pub fn from_config_profile<P: AsRef<Path>>(path: P, profile: &str) -> Result<ApiAIClient, ConfigError> {
+        Result::Ok(ConfigFile::load(path)?.profile(Option::Some(profile))?.builder().build()?)
+    }
+
+    /**
+    * Replace the transport with a HyperTransport using the given connection pool settings, keeping
+    * the proxy and TLS settings.
+    *
+    */
+    pub fn set_pool_config(&mut self, config: PoolConfig) {
+        self.transport_settings.pool = config;
+        self.rebuild_transport();
+    }
+
+    /**
+    * Replace the transport with a HyperTransport that connects through the given proxy, or
+    * directly if it is None, instead of following HTTPS_PROXY. The pool and TLS settings are
+    * kept.
+    *
+    */
+    pub fn set_proxy(&mut self, proxy: Option<ProxyConfig>) {
+        self.transport_settings.proxy = match proxy {
+            Option::Some(proxy) => ProxyMode::Via(proxy),
+            Option::None => ProxyMode::Direct
+        };
+        self.rebuild_transport();
+    }
+
+    /**
+    * Replace the transport with a HyperTransport that connects with the given TLS settings,
+    * keeping the pool and proxy settings.
+    *
+    */
+    pub fn set_tls_config(&mut self, tls: TlsConfig) {
+        self.transport_settings.tls = tls;
+        self.rebuild_transport();
+    }
+
+    fn rebuild_transport(&mut self) {
+        self.transport = Arc::new(HyperTransport::from_settings(self.transport_settings.clone()));
+    }
+
+    /**
+    * View this client as an AsyncApiAIClient that sends requests through the same transport.
+    *
+    * The returned futures resolve on the calling thread as soon as the transport returns.
+    */
+    pub fn as_async(&self) -> AsyncApiAIClient {
+        AsyncApiAIClient{
+            version: self.version.clone(),
+            access_token: self.access_token.clone(),
+            base_url: self.base_url.clone(),
+            transport: Rc::new(BlockingTransport(self.transport.clone())),
+            timeouts: self.timeouts.clone()
+        }
+    }
+
     /**
     * Carry out an API.ai query
     *
     */
     pub fn query(&self, query: ApiRequest) -> Result<ApiResponse, ApiError>{
+        self.query_with_attempts(query).result
+    }
+
+    /**
+    * Carry out an API.ai query using the given timeouts instead of the client's own.
+    *
+    */
+    pub fn query_with_timeouts(&self, query: ApiRequest, timeouts: Timeouts) -> Result<ApiResponse, ApiError>{
+        self.execute(&query, timeouts).result
+    }
 
-        let ssl = NativeTlsClient::new().unwrap();
-        let connector = HttpsConnector::new(ssl);
-        let client = Client::with_connector(connector);
+    /**
+    * Carry out an API.ai query, retrying transient failures according to the retry policy and
+    * reporting how many attempts were made.
+    *
+    */
+    pub fn query_with_attempts(&self, query: ApiRequest) -> QueryOutcome{
+        self.execute(&query, self.timeouts.clone())
+    }
 
-        let query_url = self.base_url.clone() + "/query?v=" + self.version.as_str();
+    /**
+    * Carry out an API.ai query with recorded speech instead of text.
+    *
+    * The request is sent as a multipart form with the audio streamed from `audio` as it is sent.
+    * The transcript is returned in `result.resolvedQuery`. As the audio can only be read once,
+    * voice queries are not retried.
+    *
+    */
+    pub fn query_voice(&self, query: ApiRequest, audio: AudioSource, content_type: AudioContentType) -> Result<ApiResponse, ApiError>{
+
+        let request = serialize_api_request(&query)?;
+        let (multipart_type, body) = multipart_body(request, audio, &content_type);
 
-        client.post(query_url.as_str())
-            .header(Authorization(Bearer {token: self.access_token.clone() }))
-            .header(ContentType::json())
-            .body(serde_json::to_string(&query).unwrap().as_str())
-            .send()
-            .map_err(map_hyper_to_api_err)
-            .and_then(deserialize_api_response)
+        let request = HttpRequest {
+            method: HttpMethod::Post,
+            url: self.base_url.clone() + "/query?v=" + self.version.as_str(),
+            headers: vec![
+                (String::from("Authorization"), format!("Bearer {}", self.access_token)),
+                (String::from("Content-Type"), multipart_type),
+            ],
+            body: Vec::new(),
+            timeouts: self.timeouts.clone(),
+        };
 
+        self.rate_limited(request, |request| self.transport.send_stream(request, body).and_then(deserialize_api_response))
     }
-}
 
-fn deserialize_api_response(mut response : Response) -> Result<ApiResponse, ApiError>{
-    let mut result_buffer = String::new();
-    match response.read_to_string(&mut result_buffer) {
-        Result::Ok(_) => {
-                serde_json::from_str(result_buffer.as_str()).map_err(map_serde_to_api_err)
-        },
-        Result::Err(err) => Result::Err(map_hyper_to_api_err(err))
+    /**
+    * Call one of the API.ai REST endpoints other than /query with the client's auth headers, rate
+    * limiter and timeouts. `path` is appended to the base url a segment at a time, so names are
+    * escaped as needed, and the API version is added to `params`.
+    *
+    * The response is returned once its status has been checked. These calls are not retried.
+    */
+    pub(crate) fn call_endpoint(&self, method: HttpMethod, path: &[&str], params: &[(&str, &str)], body: Option<Vec<u8>>) -> Result<HttpResponse, ApiError>{
+
+        let invalid_base_url = || ApiError::HttpError(format!("Invalid base url: {}", self.base_url));
+
+        let mut url = Url::parse(&self.base_url).map_err(|_| invalid_base_url())?;
+        url.path_segments_mut().map_err(|_| invalid_base_url())?
+            .pop_if_empty()
+            .extend(path);
+        url.query_pairs_mut()
+            .extend_pairs(params)
+            .append_pair("v", &self.version);
+
+        let mut headers = vec![(String::from("Authorization"), format!("Bearer {}", self.access_token))];
+        if body.is_some() {
+            headers.push((String::from("Content-Type"), String::from("application/json")));
+        }
+
+        let request = HttpRequest {
+            method: method,
+            url: url.into_string(),
+            headers: headers,
+            body: body.unwrap_or_default(),
+            timeouts: self.timeouts.clone(),
+        };
+
+        self.rate_limited(request, |request| self.transport.send(request).and_then(check_api_status))
     }
 
-}
+    /**
+    * Make a single attempt once the rate limiter allows it, holding the limiter closed if the
+    * server asks us to back off. Time spent waiting on the limiter counts against the request
+    * deadline.
+    */
+    fn rate_limited<T, F: FnOnce(HttpRequest) -> Result<T, ApiError>>(&self, mut request: HttpRequest, send: F) -> Result<T, ApiError>{
 
+        if let Option::Some(ref limiter) = self.rate_limiter {
+            let started = Instant::now();
+            limiter.acquire(&self.access_token, request.timeouts.request)?;
 
-fn map_hyper_to_api_err<E>(err : E) ->ApiError where E : std::error::Error {
-    ApiError::HttpError(String::from(err.description()))
-}
+            if let Option::Some(deadline) = request.timeouts.request {
+                let remaining = deadline.checked_sub(started.elapsed()).ok_or_else(|| deadline_exceeded(deadline))?;
+                request.timeouts.request = Option::Some(remaining);
+            }
+        }
+
+        let result = send(request);
+
+        if let (&Option::Some(ref limiter), &Result::Err(ApiError::RateLimited(ref details))) = (&self.rate_limiter, &result) {
+            if let Option::Some(retry_after) = details.retry_after {
+                limiter.note_retry_after(&self.access_token, retry_after);
+            }
+        }
+
+        result
+    }
+
+    /**
+    * The query is serialized once, before the rate limiter or the transport are involved, so one
+    * that can't be serialized is never sent. Any Serialize is taken so that the tests can make it
+    * fail; an ApiRequest always serializes.
+    */
+    pub(crate) fn execute<T: Serialize>(&self, query: &T, timeouts: Timeouts) -> QueryOutcome{
+
+        let body = match serialize_api_request(query) {
+            Result::Ok(body) => body,
+            Result::Err(err) => return QueryOutcome{ result: Result::Err(err), attempts: 0 }
+        };
 
+        let started = Instant::now();
+        let deadline = timeouts.request;
 
-fn map_serde_to_api_err(err : serde_json::Error) -> ApiError {
+        let mut client = self.as_async();
+        client.timeouts = timeouts;
 
-    ApiError::SerializationError(err)
+        let mut attempts = 0;
+
+        loop {
+            if let Option::Some(ref limiter) = self.rate_limiter {
+                let remaining = deadline.map(|deadline| deadline.checked_sub(started.elapsed()).unwrap_or(Duration::from_secs(0)));
+                if let Result::Err(err) = limiter.acquire(&self.access_token, remaining) {
+                    return QueryOutcome{ result: Result::Err(err), attempts: attempts };
+                }
+
+                if let Option::Some(deadline) = deadline {
+                    match deadline.checked_sub(started.elapsed()) {
+                        Option::Some(remaining) => client.timeouts.request = Option::Some(remaining),
+                        Option::None => return QueryOutcome{ result: Result::Err(deadline_exceeded(deadline)), attempts: attempts }
+                    }
+                }
+            }
+
+            attempts += 1;
+            let result = client.query_body(body.clone()).wait();
+
+            if let (&Option::Some(ref limiter), &Result::Err(ApiError::RateLimited(ref details))) = (&self.rate_limiter, &result) {
+                if let Option::Some(retry_after) = details.retry_after {
+                    limiter.note_retry_after(&self.access_token, retry_after);
+                }
+            }
+
+            let delay = match result {
+                Result::Err(ref err) => self.retry_policy.retry_delay(err, attempts),
+                Result::Ok(_) => Option::None
+            };
+
+            // Don't start another attempt if backing off would take us past the deadline
+            let delay = match (delay, deadline) {
+                (Option::Some(delay), Option::Some(deadline)) if started.elapsed() + delay >= deadline => Option::None,
+                (delay, _) => delay
+            };
+
+            match delay {
+                Option::Some(delay) => thread::sleep(delay),
+                Option::None => return QueryOutcome{ result: result, attempts: attempts }
+            }
+
+            if let Option::Some(deadline) = deadline {
+                match deadline.checked_sub(started.elapsed()) {
+                    Option::Some(remaining) => client.timeouts.request = Option::Some(remaining),
+                    Option::None => return QueryOutcome{ result: Result::Err(deadline_exceeded(deadline)), attempts: attempts }
+                }
+            }
+        }
+    }
+}
+
+/**
+* The result of a query along with the number of attempts it took.
+*
+*/
+pub struct QueryOutcome{
+    pub result: Result<ApiResponse, ApiError>,
+    pub attempts: u32,
 }
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
 
 /**
 * Error message return from API.ai
+*
+* Non-success statuses reported by api.ai (either as the HTTP status code or in the `status` object
+* of the body) are mapped onto a specific variant carrying the details of the failure.
 */
 #[derive(Debug)]
 pub enum ApiError{
     SerializationError(serde_json::Error),
-    HttpError(String)
+    RequestSerialization(serde_json::Error),
+    TlsInit(TlsError),
+    Auth(AuthError),
+    Io(io::Error),
+    Timeout(io::Error),
+    HttpError(String),
+    InvalidRequest(String),
+    BadRequest(ApiErrorDetails),
+    Unauthorized(ApiErrorDetails),
+    NotFound(ApiErrorDetails),
+    RateLimited(ApiErrorDetails),
+    ServerError(ApiErrorDetails),
+    UnexpectedStatus(ApiErrorDetails),
+}
+
+/**
+* Details of a non-success status returned by API.ai
+*
+*/
+#[derive(Debug)]
+pub struct ApiErrorDetails{
+    /**
+    * The status code reported by api.ai
+    */
+    pub code: u16,
+    /**
+    * Error type from the status object e.g. `unauthorized`. Empty if the body had no status.
+    */
+    pub error_type: String,
+    pub error_details: Option<String>,
+    /**
+    * The raw body of the response
+    */
+    pub body: String,
+    /**
+    * How long the server asked us to wait before retrying (from the Retry-After header)
+    */
+    pub retry_after: Option<Duration>,
+}
+
+impl ApiError {
+
+    /**
+    * Details of the failure if api.ai returned a non-success status
+    */
+    pub fn details(&self) -> Option<&ApiErrorDetails> {
+        match *self {
+            ApiError::BadRequest(ref details) |
+            ApiError::Unauthorized(ref details) |
+            ApiError::NotFound(ref details) |
+            ApiError::RateLimited(ref details) |
+            ApiError::ServerError(ref details) |
+            ApiError::UnexpectedStatus(ref details) => Option::Some(details),
+            _ => Option::None
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ApiError::SerializationError(ref err) => write!(f, "Failed to parse API.ai response: {}", err),
+            ApiError::RequestSerialization(ref err) => write!(f, "Failed to serialize API.ai request: {}", err),
+            ApiError::TlsInit(ref err) => write!(f, "Failed to initialise TLS: {}", err),
+            ApiError::Auth(ref err) => write!(f, "Failed to authenticate: {}", err),
+            ApiError::Io(ref err) => write!(f, "Connection error calling API.ai: {}", err),
+            ApiError::Timeout(ref err) => write!(f, "Timed out calling API.ai: {}", err),
+            ApiError::HttpError(ref msg) => write!(f, "HTTP error calling API.ai: {}", msg),
+            ApiError::InvalidRequest(ref msg) => write!(f, "Invalid request: {}", msg),
+            _ => {
+                let details = self.details().unwrap();
+                write!(f, "API.ai returned status {} ({})", details.code, details.error_type)?;
+                match details.error_details {
+                    Option::Some(ref msg) => write!(f, ": {}", msg),
+                    Option::None => Result::Ok(())
+                }
+            }
+        }
+    }
+}
+
+impl error::Error for ApiError {
+    fn description(&self) -> &str {
+        match *self {
+            ApiError::SerializationError(_) => "failed to parse API.ai response",
+            ApiError::RequestSerialization(_) => "failed to serialize API.ai request",
+            ApiError::TlsInit(_) => "failed to initialise TLS",
+            ApiError::Auth(_) => "failed to authenticate",
+            ApiError::Io(_) => "connection error calling API.ai",
+            ApiError::Timeout(_) => "timed out calling API.ai",
+            ApiError::HttpError(_) => "HTTP error calling API.ai",
+            ApiError::InvalidRequest(_) => "invalid request",
+            ApiError::BadRequest(_) => "bad request",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::NotFound(_) => "not found",
+            ApiError::RateLimited(_) => "rate limited",
+            ApiError::ServerError(_) => "API.ai server error",
+            ApiError::UnexpectedStatus(_) => "unexpected status",
+        }
+    }
+
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            ApiError::SerializationError(ref err) => Option::Some(err),
+            ApiError::RequestSerialization(ref err) => Option::Some(err),
+            ApiError::TlsInit(ref err) => Option::Some(err),
+            ApiError::Auth(ref err) => Option::Some(err),
+            ApiError::Io(ref err) => Option::Some(err),
+            ApiError::Timeout(ref err) => Option::Some(err),
+            _ => Option::None
+        }
+    }
+}
+
+/**
+* Serialize a request body for one of the REST endpoints
+*/
+pub(crate) fn json_body<T: Serialize>(value: &T) -> Result<Vec<u8>, ApiError>{
+    serde_json::to_vec(value).map_err(ApiError::RequestSerialization)
+}
+
+/**
+* Parse the JSON body of a successful response from one of the REST endpoints
+*/
+pub(crate) fn parse_body<T: Deserialize>(response: HttpResponse) -> Result<T, ApiError>{
+    serde_json::from_slice(&response.body).map_err(ApiError::SerializationError)
+}
+
+/**
+* Just enough of a response body to read the status object from it
+*/
+#[derive(Deserialize)]
+struct StatusEnvelope{
+    status: ApiStatus
+}
+
+/**
+* Check the HTTP status and any `status` object in the body, mapping failures onto an ApiError.
+*
+*/
+pub(crate) fn check_api_status(response: HttpResponse) -> Result<HttpResponse, ApiError>{
+
+    let status = serde_json::from_slice::<StatusEnvelope>(&response.body)
+        .ok()
+        .map(|envelope| envelope.status);
+
+    let code = match status {
+        Option::Some(ref status) if is_success(response.status) && status.code > 0 => status.code as u16,
+        _ => response.status
+    };
+
+    if is_success(code) {
+        return Result::Ok(response);
+    }
+
+    let (error_type, error_details) = match status {
+        Option::Some(status) => (status.error_type, status.error_details),
+        Option::None => (String::new(), Option::None)
+    };
+
+    Result::Err(status_error(&response, code, error_type, error_details))
+}
+
+/**
+* Read a Retry-After header, given either as a number of seconds or as an HTTP-date. A date in
+* the past means there's no need to wait.
+*/
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+
+    if let Result::Ok(secs) = value.parse::<u64>() {
+        return Option::Some(Duration::from_secs(secs));
+    }
+
+    value.parse::<HttpDate>().ok().map(|date| {
+        let at = date.0.to_timespec().sec;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|now| now.as_secs() as i64).unwrap_or(0);
+        Duration::from_secs(cmp::max(at - now, 0) as u64)
+    })
+}
+
+/**
+* Map a non-success status onto the matching ApiError variant, taking the body and any
+* Retry-After header from the response.
+*
+*/
+pub(crate) fn status_error(response: &HttpResponse, code: u16, error_type: String, error_details: Option<String>) -> ApiError{
+
+    let details = ApiErrorDetails{
+        code: code,
+        error_type: error_type,
+        error_details: error_details,
+        body: String::from_utf8_lossy(&response.body).into_owned(),
+        retry_after: response.header("Retry-After").and_then(parse_retry_after),
+    };
+
+    match code {
+        400 => ApiError::BadRequest(details),
+        401 | 403 => ApiError::Unauthorized(details),
+        404 => ApiError::NotFound(details),
+        429 => ApiError::RateLimited(details),
+        _ if code >= 500 && code < 600 => ApiError::ServerError(details),
+        _ => ApiError::UnexpectedStatus(details)
+    }
+}
+
+pub(crate) fn is_success(code: u16) -> bool {
+    code >= 200 && code < 300
 }
@@ -0,0 +1,230 @@
+use serde_json;
+use toml;
+
+use builder::{ApiAIClientBuilder, BuildError};
+use client::ApiAIClient;
+
+use std::collections::HashMap;
+use std::env;
+use std::error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+
+pub static ACCESS_TOKEN_VAR: &'static str = "APIAI_ACCESS_TOKEN";
+pub static BASE_URL_VAR: &'static str = "APIAI_BASE_URL";
+pub static VERSION_VAR: &'static str = "APIAI_VERSION";
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* Settings for a single agent. Anything left out falls back to the file's top level settings and
+* then to DEFAULT_BASE_URL and DEFAULT_VERSION.
+*
+*/
+#[derive(Clone,Debug,Default,Deserialize,PartialEq)]
+pub struct AgentProfile {
+    pub access_token: Option<String>,
+    pub base_url: Option<String>,
+    pub version: Option<String>,
+}
+
+/**
+* The contents of a client configuration file, in TOML or JSON.
+*
+* The top level holds shared settings and optionally the settings of a single agent. Named agents
+* go under `profiles`:
+*
+* ```toml
+* version = "20150910"
+* default_profile = "support"
+*
+* [profiles.support]
+* access_token = "ce2f54f8eb444d74af85f89e30ef2fd3"
+*
+* [profiles.sales]
+* access_token = "0f5e2ea1a1b04bd9a4b7f3b0c5a94b46"
+* base_url = "https://api.api.ai/v1"
+* ```
+*/
+#[derive(Clone,Debug,Default,Deserialize,PartialEq)]
+pub struct ConfigFile {
+    pub access_token: Option<String>,
+    pub base_url: Option<String>,
+    pub version: Option<String>,
+    /**
+    * Profile used by `from_config_file` when no profile is named
+    */
+    pub default_profile: Option<String>,
+    #[serde(default)]
+    pub profiles: HashMap<String, AgentProfile>,
+}
+
+/**
+* Formats a configuration file can be written in
+*/
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+}
+
+impl ConfigFile {
+
+    /**
+    * Parse a configuration file in the given format
+    */
+    pub fn parse(contents: &str, format: ConfigFormat) -> Result<ConfigFile, ConfigError> {
+        match format {
+            ConfigFormat::Toml => toml::from_str(contents).map_err(|err| ConfigError::Parse(err.to_string())),
+            ConfigFormat::Json => serde_json::from_str(contents).map_err(|err| ConfigError::Parse(err.to_string())),
+        }
+    }
+
+    /**
+    * Read a configuration file, working out the format from the `.toml` or `.json` extension or,
+    * failing that, from the contents.
+    */
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<ConfigFile, ConfigError> {
+        let path = path.as_ref();
+
+        let mut contents = String::new();
+        File::open(path).and_then(|mut file| file.read_to_string(&mut contents))
+            .map_err(ConfigError::Io)?;
+
+        let format = match path.extension().and_then(|ext| ext.to_str()) {
+            Option::Some(ext) if ext.eq_ignore_ascii_case("json") => ConfigFormat::Json,
+            Option::Some(ext) if ext.eq_ignore_ascii_case("toml") => ConfigFormat::Toml,
+            _ if contents.trim_left().starts_with('{') => ConfigFormat::Json,
+            _ => ConfigFormat::Toml
+        };
+
+        ConfigFile::parse(&contents, format)
+    }
+
+    /**
+    * The settings of the named profile, or of the default one if `name` is None.
+    *
+    * Without a name the `default_profile` is used if set, then the top level settings if they
+    * include an access token, then the only profile if there is exactly one.
+    */
+    pub fn profile(&self, name: Option<&str>) -> Result<AgentProfile, ConfigError> {
+
+        let name = match name.or_else(|| self.default_profile.as_ref().map(|name| name.as_str())) {
+            Option::Some(name) => Option::Some(name),
+            Option::None if self.access_token.is_some() => Option::None,
+            Option::None if self.profiles.len() == 1 => self.profiles.keys().next().map(|name| name.as_str()),
+            Option::None => Option::None
+        };
+
+        let profile = match name {
+            Option::Some(name) => self.profiles.get(name).cloned()
+                .ok_or_else(|| ConfigError::UnknownProfile(String::from(name)))?,
+            Option::None => AgentProfile::default()
+        };
+
+        Result::Ok(AgentProfile {
+            access_token: profile.access_token.or_else(|| self.access_token.clone()),
+            base_url: profile.base_url.or_else(|| self.base_url.clone()),
+            version: profile.version.or_else(|| self.version.clone()),
+        })
+    }
+}
+
+impl AgentProfile {
+
+    /**
+    * A builder carrying these settings, with defaults for anything missing
+    */
+    pub fn builder(self) -> ApiAIClientBuilder {
+        let mut builder = ApiAIClient::builder();
+
+        if let Option::Some(access_token) = self.access_token {
+            builder = builder.access_token(access_token);
+        }
+        if let Option::Some(base_url) = self.base_url {
+            builder = builder.base_url(base_url);
+        }
+        if let Option::Some(version) = self.version {
+            builder = builder.version(version);
+        }
+
+        builder
+    }
+}
+
+/**
+* Read agent settings using the given lookup for environment variables.
+*
+*/
+pub(crate) fn profile_from_vars<F: Fn(&str) -> Option<String>>(var: F) -> AgentProfile {
+    let var = |name: &str| var(name).and_then(|value| {
+        if value.trim().is_empty() { Option::None } else { Option::Some(value) }
+    });
+
+    AgentProfile {
+        access_token: var(ACCESS_TOKEN_VAR),
+        base_url: var(BASE_URL_VAR),
+        version: var(VERSION_VAR),
+    }
+}
+
+/**
+* Read agent settings from APIAI_ACCESS_TOKEN, APIAI_BASE_URL and APIAI_VERSION.
+*
+*/
+pub fn profile_from_env() -> AgentProfile {
+    profile_from_vars(|name| env::var(name).ok())
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* Failure to load client settings from the environment or a configuration file
+*
+*/
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Parse(String),
+    UnknownProfile(String),
+    Invalid(BuildError),
+}
+
+impl From<BuildError> for ConfigError {
+    fn from(err: BuildError) -> ConfigError {
+        ConfigError::Invalid(err)
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConfigError::Io(ref err) => write!(f, "Failed to read API.ai configuration: {}", err),
+            ConfigError::Parse(ref msg) => write!(f, "Failed to parse API.ai configuration: {}", msg),
+            ConfigError::UnknownProfile(ref name) => write!(f, "No API.ai agent profile named '{}'", name),
+            ConfigError::Invalid(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl error::Error for ConfigError {
+    fn description(&self) -> &str {
+        match *self {
+            ConfigError::Io(_) => "failed to read API.ai configuration",
+            ConfigError::Parse(_) => "failed to parse API.ai configuration",
+            ConfigError::UnknownProfile(_) => "unknown API.ai agent profile",
+            ConfigError::Invalid(_) => "invalid API.ai client configuration",
+        }
+    }
+
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            ConfigError::Io(ref err) => Option::Some(err),
+            ConfigError::Invalid(ref err) => Option::Some(err),
+            _ => Option::None
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use client::{ApiAIClient, ApiError, json_body, parse_body};
+use structure::ApiContext;
+use transport::HttpMethod;
+
+
+/**
+* Reply to adding contexts, naming the contexts that were set
+*/
+#[derive(Deserialize)]
+struct ContextNames {
+    #[serde(default)]
+    names: Vec<String>,
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* The contexts endpoint inspects and changes the contexts of a conversation outside of a query,
+* e.g. to reset a session when a conversation is handed over to a human.
+*
+* Every call is scoped to a session id, the same `sessionId` sent with queries.
+*/
+impl ApiAIClient {
+
+    /**
+    * List the contexts currently active in a session
+    *
+    */
+    pub fn contexts(&self, session_id: &str) -> Result<Vec<ApiContext>, ApiError>{
+        self.call_endpoint(HttpMethod::Get, &["contexts"], &[("sessionId", session_id)], Option::None)
+            .and_then(parse_body)
+    }
+
+    /**
+    * Get a single context from a session. Fails with ApiError::NotFound if it isn't active.
+    *
+    */
+    pub fn context(&self, session_id: &str, name: &str) -> Result<ApiContext, ApiError>{
+        self.call_endpoint(HttpMethod::Get, &["contexts", name], &[("sessionId", session_id)], Option::None)
+            .and_then(parse_body)
+    }
+
+    /**
+    * Add contexts to a session, replacing any with the same name, and return their names.
+    *
+    */
+    pub fn add_contexts(&self, session_id: &str, contexts: &[ApiContext]) -> Result<Vec<String>, ApiError>{
+        let body = json_body(&contexts)?;
+
+        self.call_endpoint(HttpMethod::Post, &["contexts"], &[("sessionId", session_id)], Option::Some(body))
+            .and_then(parse_body::<ContextNames>)
+            .map(|reply| reply.names)
+    }
+
+    /**
+    * Remove every context from a session
+    *
+    */
+    pub fn delete_contexts(&self, session_id: &str) -> Result<(), ApiError>{
+        self.call_endpoint(HttpMethod::Delete, &["contexts"], &[("sessionId", session_id)], Option::None)
+            .map(|_| ())
+    }
+
+    /**
+    * Remove a single context from a session
+    *
+    */
+    pub fn delete_context(&self, session_id: &str, name: &str) -> Result<(), ApiError>{
+        self.call_endpoint(HttpMethod::Delete, &["contexts", name], &[("sessionId", session_id)], Option::None)
+            .map(|_| ())
+    }
+}
@@ -0,0 +1,98 @@
+use client::{ApiAIClient, ApiError, json_body, parse_body};
+use structure::{Entity, EntityEntry, EntitySummary};
+use transport::HttpMethod;
+
+
+/**
+* Reply to creating an entity, carrying the id api.ai gave it
+*/
+#[derive(Deserialize)]
+struct CreatedEntity {
+    id: String,
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* The entities endpoint manages the entities of the agent the access token belongs to, which
+* needs a developer access token rather than a client one.
+*
+* Entities can be addressed either by their id or by their name.
+*/
+impl ApiAIClient {
+
+    /**
+    * List the agent's entities
+    *
+    */
+    pub fn entities(&self) -> Result<Vec<EntitySummary>, ApiError>{
+        self.call_endpoint(HttpMethod::Get, &["entities"], &[], Option::None)
+            .and_then(parse_body)
+    }
+
+    /**
+    * Get an entity along with all of its entries
+    *
+    */
+    pub fn entity(&self, id_or_name: &str) -> Result<Entity, ApiError>{
+        self.call_endpoint(HttpMethod::Get, &["entities", id_or_name], &[], Option::None)
+            .and_then(parse_body)
+    }
+
+    /**
+    * Create an entity and return its id
+    *
+    */
+    pub fn create_entity(&self, entity: &Entity) -> Result<String, ApiError>{
+        let body = json_body(entity)?;
+
+        self.call_endpoint(HttpMethod::Post, &["entities"], &[], Option::Some(body))
+            .and_then(parse_body::<CreatedEntity>)
+            .map(|created| created.id)
+    }
+
+    /**
+    * Replace an entity, including all of its entries
+    *
+    */
+    pub fn update_entity(&self, id_or_name: &str, entity: &Entity) -> Result<(), ApiError>{
+        let body = json_body(entity)?;
+
+        self.call_endpoint(HttpMethod::Put, &["entities", id_or_name], &[], Option::Some(body))
+            .map(|_| ())
+    }
+
+    pub fn delete_entity(&self, id_or_name: &str) -> Result<(), ApiError>{
+        self.call_endpoint(HttpMethod::Delete, &["entities", id_or_name], &[], Option::None)
+            .map(|_| ())
+    }
+
+    /**
+    * Add entries to an entity, leaving its existing entries in place
+    *
+    */
+    pub fn add_entity_entries(&self, id_or_name: &str, entries: &[EntityEntry]) -> Result<(), ApiError>{
+        self.send_entries(HttpMethod::Post, id_or_name, json_body(&entries)?)
+    }
+
+    /**
+    * Replace the synonyms of existing entries, matched on their value
+    *
+    */
+    pub fn update_entity_entries(&self, id_or_name: &str, entries: &[EntityEntry]) -> Result<(), ApiError>{
+        self.send_entries(HttpMethod::Put, id_or_name, json_body(&entries)?)
+    }
+
+    /**
+    * Remove the entries with the given values from an entity
+    *
+    */
+    pub fn delete_entity_entries(&self, id_or_name: &str, values: &[String]) -> Result<(), ApiError>{
+        self.send_entries(HttpMethod::Delete, id_or_name, json_body(&values)?)
+    }
+
+    fn send_entries(&self, method: HttpMethod, id_or_name: &str, body: Vec<u8>) -> Result<(), ApiError>{
+        self.call_endpoint(method, &["entities", id_or_name, "entries"], &[], Option::Some(body))
+            .map(|_| ())
+    }
+}
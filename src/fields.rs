@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+use serde::de;
+use serde::ser::SerializeMap;
+use serde_json::{self, Map, Value};
+
+
+/**
+* With the `strict` feature, fields that are missing, malformed or unknown are errors rather than
+* being defaulted or kept in `extra`.
+*/
+pub(crate) const STRICT: bool = cfg!(feature = "strict");
+
+/**
+* Reads the fields of a response object one at a time. Whatever isn't read is left over for the
+* struct's `extra` map, so that fields added by api.ai survive a round trip.
+*
+* A field that is there but can't be read is also left in `extra`, as it was sent, and the struct
+* gets the default. FieldWriter writes the value from `extra` back in its place.
+*/
+pub(crate) struct Fields {
+    object: &'static str,
+    fields: Map<String, Value>,
+    exact: bool,
+}
+
+impl Fields {
+
+    pub fn new<E: de::Error>(object: &'static str, value: Value) -> Result<Fields, E> {
+        match value {
+            Value::Object(fields) => Result::Ok(Fields{ object: object, fields: fields, exact: false }),
+            other => Result::Err(E::custom(format!("expected {} to be an object, got {}", object, other)))
+        }
+    }
+
+    /**
+    * Like `new`, but fields that are missing or can't be read are always errors. Unknown fields
+    * are still kept unless the `strict` feature is on. Used for objects that are kept whole
+    * elsewhere when they can't be read, such as rich messages.
+    */
+    pub fn exact<E: de::Error>(object: &'static str, value: Value) -> Result<Fields, E> {
+        Fields::new(object, value).map(|fields| Fields{ exact: true, ..fields })
+    }
+
+    /**
+    * A field api.ai always sends. If it's missing, null or can't be read the default is used.
+    */
+    pub fn required<T, E>(&mut self, name: &str) -> Result<T, E>
+        where T: Deserialize + Default,
+              E: de::Error
+    {
+        let strict = STRICT || self.exact;
+
+        match self.fields.remove(name) {
+            Option::None | Option::Some(Value::Null) if strict => {
+                Result::Err(E::custom(format!("missing field `{}` in {}", name, self.object)))
+            },
+            Option::None | Option::Some(Value::Null) => Result::Ok(T::default()),
+            Option::Some(value) => self.parse(name, value, T::default)
+        }
+    }
+
+    /**
+    * A field that may be left out, in which case it is given `default()`
+    */
+    pub fn optional<T, E, F>(&mut self, name: &str, default: F) -> Result<T, E>
+        where T: Deserialize,
+              E: de::Error,
+              F: FnOnce() -> T
+    {
+        match self.fields.remove(name) {
+            Option::Some(value) => self.parse(name, value, default),
+            Option::None => Result::Ok(default())
+        }
+    }
+
+    /**
+    * The fields that weren't read
+    */
+    pub fn extra<E: de::Error>(self) -> Result<Map<String, Value>, E> {
+        if STRICT && !self.fields.is_empty() {
+            let names: Vec<&str> = self.fields.keys().map(String::as_str).collect();
+            return Result::Err(E::custom(format!("unknown fields in {}: {}", self.object, names.join(", "))));
+        }
+
+        Result::Ok(self.fields)
+    }
+
+    fn parse<T, E, F>(&mut self, name: &str, value: Value, default: F) -> Result<T, E>
+        where T: Deserialize,
+              E: de::Error,
+              F: FnOnce() -> T
+    {
+        if STRICT || self.exact {
+            return serde_json::from_value(value)
+                .map_err(|err| E::custom(format!("invalid field `{}` in {}: {}", name, self.object, err)));
+        }
+
+        match serde_json::from_value(value.clone()) {
+            Result::Ok(parsed) => Result::Ok(parsed),
+            Result::Err(_) => {
+                self.fields.insert(String::from(name), value);
+                Result::Ok(default())
+            }
+        }
+    }
+}
+
+/**
+* Writes a response object's fields, the counterpart of Fields. A field whose name is also in
+* `extra` couldn't be read, so the value from `extra` is written instead of the default.
+*
+*/
+pub(crate) struct FieldWriter<'a, M: SerializeMap> {
+    map: M,
+    extra: &'a Map<String, Value>,
+}
+
+impl<'a, M: SerializeMap> FieldWriter<'a, M> {
+
+    pub fn new(map: M, extra: &'a Map<String, Value>) -> FieldWriter<'a, M> {
+        FieldWriter{ map: map, extra: extra }
+    }
+
+    pub fn field<T: Serialize + ?Sized>(&mut self, name: &str, value: &T) -> Result<(), M::Error> {
+        if self.extra.contains_key(name) {
+            return Result::Ok(());
+        }
+
+        self.map.serialize_entry(name, value)
+    }
+
+    /**
+    * A field that is left out when it's None
+    */
+    pub fn optional<T: Serialize>(&mut self, name: &str, value: &Option<T>) -> Result<(), M::Error> {
+        match *value {
+            Option::Some(ref value) => self.field(name, value),
+            Option::None => Result::Ok(())
+        }
+    }
+
+    /**
+    * Write out the fields kept in `extra`, after the ones that are modelled
+    */
+    pub fn end(mut self) -> Result<M::Ok, M::Error> {
+        for (name, value) in self.extra {
+            self.map.serialize_entry(name, value)?;
+        }
+
+        self.map.end()
+    }
+}
@@ -0,0 +1,67 @@
+use client::{ApiAIClient, ApiError, json_body, parse_body};
+use structure::{Intent, IntentSummary};
+use transport::HttpMethod;
+
+
+/**
+* Reply to creating an intent, carrying the id api.ai gave it
+*/
+#[derive(Deserialize)]
+struct CreatedIntent {
+    id: String,
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* The intents endpoint manages the intents of the agent the access token belongs to, which needs a
+* developer access token rather than a client one.
+*/
+impl ApiAIClient {
+
+    /**
+    * List the agent's intents
+    *
+    */
+    pub fn intents(&self) -> Result<Vec<IntentSummary>, ApiError>{
+        self.call_endpoint(HttpMethod::Get, &["intents"], &[], Option::None)
+            .and_then(parse_body)
+    }
+
+    /**
+    * Get an intent along with its example phrases and responses
+    *
+    */
+    pub fn intent(&self, id: &str) -> Result<Intent, ApiError>{
+        self.call_endpoint(HttpMethod::Get, &["intents", id], &[], Option::None)
+            .and_then(parse_body)
+    }
+
+    /**
+    * Create an intent and return its id
+    *
+    */
+    pub fn create_intent(&self, intent: &Intent) -> Result<String, ApiError>{
+        let body = json_body(intent)?;
+
+        self.call_endpoint(HttpMethod::Post, &["intents"], &[], Option::Some(body))
+            .and_then(parse_body::<CreatedIntent>)
+            .map(|created| created.id)
+    }
+
+    /**
+    * Replace an intent
+    *
+    */
+    pub fn update_intent(&self, id: &str, intent: &Intent) -> Result<(), ApiError>{
+        let body = json_body(intent)?;
+
+        self.call_endpoint(HttpMethod::Put, &["intents", id], &[], Option::Some(body))
+            .map(|_| ())
+    }
+
+    pub fn delete_intent(&self, id: &str) -> Result<(), ApiError>{
+        self.call_endpoint(HttpMethod::Delete, &["intents", id], &[], Option::None)
+            .map(|_| ())
+    }
+}
@@ -7,7 +7,7 @@ use std::fmt;
 * Enum defines API.ai supported languages and their text values used on the server
 *
 */
-#[derive(PartialEq)]
+#[derive(Clone,PartialEq)]
 pub enum Language {
     BrazilianPortuguese,
     ChineseCantonese,
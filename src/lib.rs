@@ -1,10 +1,31 @@
 #[macro_use]
 extern crate serde_derive;
 extern crate uuid;
+extern crate chrono;
 extern crate serde;
 extern crate serde_json;
+extern crate toml;
+extern crate zip;
+extern crate ring;
+extern crate untrusted;
+extern crate base64;
 extern crate hyper;
+#[cfg(feature = "native-tls")]
 extern crate hyper_native_tls;
+extern crate futures;
+extern crate tokio_core;
+extern crate hyper_async;
+#[cfg(feature = "native-tls")]
+extern crate hyper_tls;
+#[cfg(feature = "rustls")]
+extern crate hyper_rustls;
+#[cfg(feature = "rustls")]
+extern crate tokio_rustls;
+#[cfg(feature = "rustls")]
+extern crate tokio_io;
+#[cfg(feature = "rustls")]
+extern crate webpki_roots;
+extern crate rand;
 
 
 
@@ -14,4 +35,23 @@ mod test;
 
 pub mod lang;
 pub mod client;
+pub mod async_client;
 pub mod structure;
+pub mod parameters;
+pub mod sys_entities;
+pub mod transport;
+pub mod retry;
+pub mod rate_limit;
+pub mod builder;
+pub mod config;
+pub mod proxy;
+pub mod tls;
+pub mod voice;
+pub mod archive;
+pub mod auth;
+pub mod v2;
+mod contexts;
+mod entities;
+mod intents;
+mod user_entities;
+mod fields;
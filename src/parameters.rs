@@ -0,0 +1,114 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{self, Value};
+
+use std::collections::HashMap;
+use std::collections::hash_map::Iter;
+
+
+/**
+* Named parameter values, as found in results, contexts and event data.
+*
+* Values are kept as JSON so that lists (from `is_list` parameters), objects (such as a
+* `@sys.unit-currency` amount), numbers and booleans come through intact. The typed getters return
+* None when a parameter is missing or holds a different kind of value.
+*/
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct Parameters(HashMap<String, Value>);
+
+impl Parameters {
+
+    pub fn new() -> Parameters {
+        Parameters(HashMap::new())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.0.get(name)
+    }
+
+    pub fn get_str(&self, name: &str) -> Option<&str> {
+        self.get(name).and_then(Value::as_str)
+    }
+
+    /**
+    * A numeric parameter. Numbers sent as strings (e.g. `"42"`) are parsed.
+    */
+    pub fn get_number(&self, name: &str) -> Option<f64> {
+        match self.get(name) {
+            Option::Some(&Value::String(ref text)) => text.trim().parse().ok(),
+            Option::Some(value) => value.as_f64(),
+            Option::None => Option::None
+        }
+    }
+
+    pub fn get_list(&self, name: &str) -> Option<&Vec<Value>> {
+        self.get(name).and_then(Value::as_array)
+    }
+
+    /**
+    * Deserialize a parameter into any type, e.g. a struct matching a composite entity
+    */
+    pub fn get_as<T: Deserialize>(&self, name: &str) -> Option<T> {
+        self.get(name).and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+
+    pub fn insert<V: Into<Value>>(&mut self, name: &str, value: V) -> Option<Value> {
+        self.0.insert(String::from(name), value.into())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Value> {
+        self.0.remove(name)
+    }
+
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.0.contains_key(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> Iter<String, Value> {
+        self.0.iter()
+    }
+}
+
+impl From<HashMap<String, Value>> for Parameters {
+    fn from(values: HashMap<String, Value>) -> Parameters {
+        Parameters(values)
+    }
+}
+
+impl From<HashMap<String, String>> for Parameters {
+    fn from(values: HashMap<String, String>) -> Parameters {
+        Parameters(values.into_iter().map(|(name, value)| (name, Value::String(value))).collect())
+    }
+}
+
+impl<'a> IntoIterator for &'a Parameters {
+    type Item = (&'a String, &'a Value);
+    type IntoIter = Iter<'a, String, Value>;
+
+    fn into_iter(self) -> Iter<'a, String, Value> {
+        self.0.iter()
+    }
+}
+
+impl Serialize for Parameters {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl Deserialize for Parameters {
+    fn deserialize<D>(deserializer: D) -> Result<Parameters, D::Error>
+        where D: Deserializer
+    {
+        HashMap::deserialize(deserializer).map(Parameters)
+    }
+}
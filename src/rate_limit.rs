@@ -0,0 +1,218 @@
+use builder::ConfigProblem;
+use client::{ApiError, ApiErrorDetails};
+
+use std::cmp;
+use std::collections::HashMap;
+use std::io;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/**
+* The longest a Retry-After reply holds a bucket closed for, so that a bogus value can't block a
+* token for good
+*/
+const MAX_BLOCK_SECS: u64 = 24 * 60 * 60;
+
+/**
+* A quota of `requests` queries every `per`, e.g. 600 queries per minute.
+*
+* Up to `requests` queries can be sent in a burst, after which the allowance refills evenly over
+* the period. Both must be greater than zero.
+*/
+#[derive(Clone,Debug,PartialEq)]
+pub struct RateLimit {
+    pub requests: u32,
+    pub per: Duration,
+}
+
+impl RateLimit {
+
+    pub fn per_second(requests: u32) -> RateLimit {
+        RateLimit{ requests: requests, per: Duration::from_secs(1) }
+    }
+
+    pub fn per_minute(requests: u32) -> RateLimit {
+        RateLimit{ requests: requests, per: Duration::from_secs(60) }
+    }
+
+    fn check(&self) -> Result<(), ConfigProblem> {
+        if self.requests == 0 || self.per == Duration::from_secs(0) {
+            Result::Err(ConfigProblem::ZeroRateLimit)
+        } else {
+            Result::Ok(())
+        }
+    }
+
+    fn tokens_per_second(&self) -> f64 {
+        self.requests as f64 / duration_secs(self.per)
+    }
+}
+
+/**
+* What the limiter does when a query would go over the limit
+*
+*/
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum RateLimitMode {
+    /**
+    * Block the calling thread until the query can be sent
+    */
+    Wait,
+    /**
+    * Return ApiError::RateLimited straight away without sending the query
+    */
+    FailFast,
+}
+
+/**
+* A client side token bucket rate limiter for the query endpoint.
+*
+* Each access token gets its own bucket, using the default limit unless one was set for that
+* token with `set_token_limit`. Attach the limiter to an ApiAIClient in an Arc; clones of the
+* client then share its buckets. If api.ai replies 429 with a Retry-After header the bucket for
+* that token is held closed until the server's wait has passed.
+*/
+pub struct RateLimiter {
+    default_limit: RateLimit,
+    mode: RateLimitMode,
+    token_limits: Mutex<HashMap<String, RateLimit>>,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+    blocked_until: Option<Instant>,
+}
+
+impl RateLimiter {
+
+    /**
+    * Create a limiter, or ConfigProblem::ZeroRateLimit if the limit allows no queries
+    */
+    pub fn new(default_limit: RateLimit, mode: RateLimitMode) -> Result<RateLimiter, ConfigProblem> {
+        default_limit.check()?;
+        Result::Ok(RateLimiter{
+            default_limit: default_limit,
+            mode: mode,
+            token_limits: Mutex::new(HashMap::new()),
+            buckets: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /**
+    * Use a different limit for queries made with the given access token. A limit allowing no
+    * queries is refused with ConfigProblem::ZeroRateLimit.
+    */
+    pub fn set_token_limit(&self, access_token: &str, limit: RateLimit) -> Result<(), ConfigProblem> {
+        limit.check()?;
+        self.token_limits.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(String::from(access_token), limit);
+        self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(access_token);
+        Result::Ok(())
+    }
+
+    fn limit_for(&self, access_token: &str) -> RateLimit {
+        self.token_limits.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(access_token)
+            .cloned()
+            .unwrap_or_else(|| self.default_limit.clone())
+    }
+
+    /**
+    * Take a token from the bucket for `access_token`, waiting or failing according to the mode.
+    *
+    * In Wait mode `timeout` is the time left before the caller's request deadline. If a token
+    * won't be available in that time ApiError::Timeout is returned rather than waiting.
+    */
+    pub fn acquire(&self, access_token: &str, timeout: Option<Duration>) -> Result<(), ApiError> {
+        let started = Instant::now();
+
+        loop {
+            let wait = match self.try_acquire(access_token) {
+                Option::None => return Result::Ok(()),
+                Option::Some(wait) => wait
+            };
+
+            match self.mode {
+                RateLimitMode::Wait => {
+                    if let Option::Some(timeout) = timeout {
+                        if started.elapsed() + wait > timeout {
+                            return Result::Err(ApiError::Timeout(io::Error::new(io::ErrorKind::TimedOut,
+                                format!("Rate limit wait of {:?} would pass the request deadline", wait))));
+                        }
+                    }
+                    thread::sleep(wait)
+                },
+                RateLimitMode::FailFast => return Result::Err(ApiError::RateLimited(ApiErrorDetails{
+                    code: 429,
+                    error_type: String::from("client_rate_limited"),
+                    error_details: Option::Some(String::from("Client side rate limit exceeded for this access token")),
+                    body: String::new(),
+                    retry_after: Option::Some(wait),
+                }))
+            }
+        }
+    }
+
+    /**
+    * Take a token if one is available, otherwise return how long until one will be.
+    */
+    fn try_acquire(&self, access_token: &str) -> Option<Duration> {
+
+        let limit = self.limit_for(access_token);
+        let now = Instant::now();
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let bucket = buckets.entry(String::from(access_token)).or_insert_with(|| Bucket{
+            tokens: limit.requests as f64,
+            last_refill: now,
+            blocked_until: Option::None,
+        });
+
+        let elapsed = duration_secs(now.duration_since(bucket.last_refill));
+        bucket.tokens = (bucket.tokens + elapsed * limit.tokens_per_second()).min(limit.requests as f64);
+        bucket.last_refill = now;
+
+        if let Option::Some(until) = bucket.blocked_until {
+            if until > now {
+                return Option::Some(until.duration_since(now));
+            }
+            bucket.blocked_until = Option::None;
+        }
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Option::None
+        } else {
+            let secs = (1.0 - bucket.tokens) / limit.tokens_per_second();
+            Option::Some(Duration::from_millis((secs * 1000.0).ceil() as u64))
+        }
+    }
+
+    /**
+    * Hold the bucket for `access_token` closed for the wait the server asked for in Retry-After,
+    * up to a day at most.
+    */
+    pub fn note_retry_after(&self, access_token: &str, retry_after: Duration) {
+        let retry_after = cmp::min(retry_after, Duration::from_secs(MAX_BLOCK_SECS));
+        let until = match Instant::now().checked_add(retry_after) {
+            Option::Some(until) => until,
+            Option::None => return
+        };
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Option::Some(bucket) = buckets.get_mut(access_token) {
+            bucket.blocked_until = match bucket.blocked_until {
+                Option::Some(current) if current > until => Option::Some(current),
+                _ => Option::Some(until)
+            };
+        }
+    }
+}
+
+fn duration_secs(duration: Duration) -> f64 {
+    duration.as_secs() as f64 + duration.subsec_nanos() as f64 / 1e9
+}
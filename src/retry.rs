@@ -0,0 +1,127 @@
+use rand;
+
+use client::ApiError;
+
+use std::cmp;
+use std::io::ErrorKind;
+use std::time::Duration;
+
+/**
+* RetryPolicy controls how ApiAIClient retries queries that fail for transient reasons.
+*
+* Retries back off exponentially from `base_delay`, doubling on each attempt up to `max_delay`.
+* With `jitter` enabled a random delay between zero and the backoff is used instead so that many
+* clients failing at once don't retry in lock step.
+*
+* Only connection failures, the server statuses in `retry_statuses` and (optionally) 429 replies
+* with a Retry-After header are retried. Client errors such as 400 and 401, and a 429 without
+* Retry-After (usually a spent quota), are never retried. The client waits at least as long as
+* Retry-After asks, or gives up if it exceeds `max_delay`.
+*
+* The default policy makes a single attempt, matching the behaviour of a client without retries.
+*/
+#[derive(Clone,Debug)]
+pub struct RetryPolicy {
+    /**
+    * Total number of attempts including the first one
+    */
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub jitter: bool,
+    /**
+    * Retry when the connection is reset, aborted or closed early
+    */
+    pub retry_connection_errors: bool,
+    /**
+    * Server error statuses that should be retried. Codes below 500 are ignored.
+    */
+    pub retry_statuses: Vec<u16>,
+    /**
+    * Retry 429 responses that carry a Retry-After header, waiting at least that long
+    */
+    pub retry_rate_limited: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 1,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+            retry_connection_errors: true,
+            retry_statuses: vec![502, 503, 504],
+            retry_rate_limited: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+
+    /**
+    * A policy that makes up to `max_attempts` attempts with the default backoff settings.
+    */
+    pub fn exponential(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: max_attempts,
+            ..Default::default()
+        }
+    }
+
+    /**
+    * Whether the given error is worth trying again under this policy
+    */
+    pub fn is_retryable(&self, err: &ApiError) -> bool {
+        match *err {
+            ApiError::Io(ref err) => self.retry_connection_errors && match err.kind() {
+                ErrorKind::ConnectionReset |
+                ErrorKind::ConnectionAborted |
+                ErrorKind::BrokenPipe |
+                ErrorKind::UnexpectedEof => true,
+                _ => false
+            },
+            ApiError::ServerError(ref details) => self.retry_statuses.contains(&details.code),
+            ApiError::RateLimited(ref details) => self.retry_rate_limited && details.retry_after.is_some(),
+            _ => false
+        }
+    }
+
+    /**
+    * The exponential backoff (before jitter) after the given attempt, starting from 1.
+    */
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = cmp::min(attempt.saturating_sub(1), 31);
+        let millis = duration_millis(self.base_delay).saturating_mul(1 << exponent);
+
+        cmp::min(Duration::from_millis(millis), self.max_delay)
+    }
+
+    /**
+    * How long to wait before retrying after `err` on the given attempt, or None to give up.
+    */
+    pub fn retry_delay(&self, err: &ApiError, attempt: u32) -> Option<Duration> {
+
+        if attempt >= self.max_attempts || !self.is_retryable(err) {
+            return Option::None;
+        }
+
+        let backoff = self.backoff(attempt);
+        let delay = if self.jitter {
+            let millis = duration_millis(backoff) as f64 * rand::random::<f64>();
+            Duration::from_millis(millis as u64)
+        } else {
+            backoff
+        };
+
+        match err.details().and_then(|details| details.retry_after) {
+            Option::Some(retry_after) if retry_after > self.max_delay => Option::None,
+            Option::Some(retry_after) => Option::Some(cmp::max(delay, retry_after)),
+            Option::None => Option::Some(delay)
+        }
+    }
+}
+
+fn duration_millis(duration: Duration) -> u64 {
+    duration.as_secs().saturating_mul(1000) + (duration.subsec_nanos() / 1000000) as u64
+}
@@ -1,15 +1,22 @@
 use uuid::Uuid;
 use lang::Language;
-use std::collections::HashMap;
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{self, Map, Value};
+use fields::{Fields, FieldWriter, STRICT};
+use parameters::Parameters;
 
 /**
 * Response from API.AI call
+*
+* This and the other response structs are read leniently: fields api.ai leaves out or sends in an
+* unexpected form are given defaults, and fields this crate doesn't model are kept in `extra` and
+* written back out when the response is serialized. Enable the `strict` feature to have any of
+* these treated as an error instead.
 */
-#[derive(Serialize,Deserialize)]
+#[derive(Default)]
 pub struct ApiResponse {
     pub id : String,
     pub timestamp : String,
-    #[serde(default="Language::default")]
     pub lang : Language,
     pub result: ApiResult,
 
@@ -24,9 +31,46 @@ pub struct ApiResponse {
     * Session ID
     *
     */
-    #[serde( rename = "sessionId" ) ]
-    pub session_id : String
+    pub session_id : String,
+
+    /**
+    * Fields that aren't modelled above
+    */
+    pub extra : Map<String, Value>
+
+}
+
+impl Serialize for ApiResponse {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("id", &self.id)?;
+        fields.field("timestamp", &self.timestamp)?;
+        fields.field("lang", &self.lang)?;
+        fields.field("result", &self.result)?;
+        fields.field("status", &self.status)?;
+        fields.field("sessionId", &self.session_id)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for ApiResponse {
+    fn deserialize<D>(deserializer: D) -> Result<ApiResponse, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::new("response", Deserialize::deserialize(deserializer)?)?;
 
+        Result::Ok(ApiResponse{
+            id: fields.required("id")?,
+            timestamp: fields.required("timestamp")?,
+            lang: fields.optional("lang", Language::default)?,
+            result: fields.required("result")?,
+            status: fields.required("status")?,
+            session_id: fields.required("sessionId")?,
+            extra: fields.extra()?,
+        })
+    }
 }
 
 
@@ -35,7 +79,7 @@ pub struct ApiResponse {
 * Result object encapsulates API result including actions, intent name (if applicable) etc.
 *
 */
-#[derive(Serialize,Deserialize)]
+#[derive(Default)]
 pub struct ApiResult {
 
     /**
@@ -47,7 +91,6 @@ pub struct ApiResult {
     * The query that was used to produce this result.
     *
     */
-    #[serde(rename = "resolvedQuery")]
     pub resolved_query : String,
     /**
     * An action to take. `Example: turn on`
@@ -60,16 +103,14 @@ pub struct ApiResult {
     * values have been collected `false` if all required parameter values have been collected
     * or if the triggered intent doesn't containt any required parameters.
     */
-    #[serde( rename = "actionIncomplete", default="default_bool") ]
     pub action_incomplete : bool,
     /**
     * A map of parameters associated with this result
     */
-    pub parameters : HashMap<String,String>,
+    pub parameters : Parameters,
     /**
     * Vector of contexts provided by the current conversation
     */
-    #[serde(default="Vec::default")]
     pub contexts : Vec<ApiContext>,
 
     /**
@@ -88,39 +129,168 @@ pub struct ApiResult {
     */
     pub score : f32,
 
+    /**
+    * Fields that aren't modelled above
+    */
+    pub extra : Map<String, Value>
 
 }
 
-fn default_bool() -> bool {false}
+impl ApiResult {
+
+    /**
+    * Decode a parameter, e.g. `result.param::<SysDate>("date")` using the types in
+    * `sys_entities`. Returns None if the parameter is missing, was left empty or can't be
+    * decoded as `T`.
+    */
+    pub fn param<T: Deserialize>(&self, name: &str) -> Option<T> {
+        self.parameters.get_as(name)
+    }
+}
+
+impl Serialize for ApiResult {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("source", &self.source)?;
+        fields.field("resolvedQuery", &self.resolved_query)?;
+        fields.field("action", &self.action)?;
+        fields.field("actionIncomplete", &self.action_incomplete)?;
+        fields.field("parameters", &self.parameters)?;
+        fields.field("contexts", &self.contexts)?;
+        fields.field("metadata", &self.metadata)?;
+        fields.field("fulfillment", &self.fulfillment)?;
+        fields.field("score", &self.score)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for ApiResult {
+    fn deserialize<D>(deserializer: D) -> Result<ApiResult, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::new("result", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(ApiResult{
+            source: fields.required("source")?,
+            resolved_query: fields.required("resolvedQuery")?,
+            action: fields.required("action")?,
+            action_incomplete: fields.optional("actionIncomplete", bool::default)?,
+            parameters: fields.required("parameters")?,
+            contexts: fields.optional("contexts", Vec::new)?,
+            metadata: fields.required("metadata")?,
+            fulfillment: fields.required("fulfillment")?,
+            score: fields.required("score")?,
+            extra: fields.extra()?,
+        })
+    }
+}
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
 /**
 * API Status encapsulates the api http status - usually 200 if all is well
 *
 */
-#[derive(Serialize,Deserialize)]
 pub struct ApiStatus{
     pub code: i32,
-    #[serde( rename = "errorType") ]
     pub error_type: String,
-    #[serde( rename = "errorDetails", skip_serializing_if = "Option::is_none") ]
     pub error_details: Option<String>,
+    pub extra: Map<String, Value>,
+}
+
+/**
+* A response without a status object got through as a success, so it defaults to one
+*/
+impl Default for ApiStatus {
+    fn default() -> ApiStatus {
+        ApiStatus{
+            code: 200,
+            error_type: String::from("success"),
+            error_details: Option::None,
+            extra: Map::new(),
+        }
+    }
+}
+
+impl Serialize for ApiStatus {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("code", &self.code)?;
+        fields.field("errorType", &self.error_type)?;
+        fields.optional("errorDetails", &self.error_details)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for ApiStatus {
+    fn deserialize<D>(deserializer: D) -> Result<ApiStatus, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::new("status", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(ApiStatus{
+            code: fields.required("code")?,
+            error_type: fields.required("errorType")?,
+            error_details: fields.optional("errorDetails", Option::default)?,
+            extra: fields.extra()?,
+        })
+    }
 }
 
 /**
 * API.ai metadata struct
 *
 */
-#[derive(Serialize,Deserialize)]
 pub struct ApiMetadata{
-    #[serde( rename = "intentId") ]
     pub intent_id: Option<String>,
-    #[serde( rename = "webhookUsed", default="default_false_string") ]
     pub webhook_used: String,
-    #[serde( rename = "webhookForSlotFillingUsed", default="default_false_string") ]
     pub webhook_slotfilling_used: String,
-    #[serde( rename = "intentName", skip_serializing_if = "Option::is_none") ]
     pub intent_name: Option<String>,
+    pub extra: Map<String, Value>,
+}
+
+impl Default for ApiMetadata {
+    fn default() -> ApiMetadata {
+        ApiMetadata{
+            intent_id: Option::None,
+            webhook_used: default_false_string(),
+            webhook_slotfilling_used: default_false_string(),
+            intent_name: Option::None,
+            extra: Map::new(),
+        }
+    }
+}
+
+impl Serialize for ApiMetadata {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("intentId", &self.intent_id)?;
+        fields.field("webhookUsed", &self.webhook_used)?;
+        fields.field("webhookForSlotFillingUsed", &self.webhook_slotfilling_used)?;
+        fields.optional("intentName", &self.intent_name)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for ApiMetadata {
+    fn deserialize<D>(deserializer: D) -> Result<ApiMetadata, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::new("metadata", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(ApiMetadata{
+            intent_id: fields.optional("intentId", Option::default)?,
+            webhook_used: fields.optional("webhookUsed", default_false_string)?,
+            webhook_slotfilling_used: fields.optional("webhookForSlotFillingUsed", default_false_string)?,
+            intent_name: fields.optional("intentName", Option::default)?,
+            extra: fields.extra()?,
+        })
+    }
 }
 
 fn default_false_string() -> String{ String::from("false") }
@@ -130,7 +300,7 @@ fn default_false_string() -> String{ String::from("false") }
 /**
 * API.ai fullfilment structure (part of results)
 */
-#[derive(Serialize,Deserialize)]
+#[derive(Default)]
 pub struct ApiFulfillment{
     /**
     * The speech to be sent back to the user
@@ -141,8 +311,36 @@ pub struct ApiFulfillment{
     * Represents an array of message objects as described in ApiMessage
     *
     */
-    #[serde(skip_serializing_if = "Option::is_none") ]
-    pub messages: Option<Vec<ApiMessage>>
+    pub messages: Option<Vec<ApiMessage>>,
+    /**
+    * Fields that aren't modelled above
+    */
+    pub extra: Map<String, Value>
+}
+
+impl Serialize for ApiFulfillment {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("speech", &self.speech)?;
+        fields.optional("messages", &self.messages)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for ApiFulfillment {
+    fn deserialize<D>(deserializer: D) -> Result<ApiFulfillment, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::new("fulfillment", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(ApiFulfillment{
+            speech: fields.required("speech")?,
+            messages: fields.optional("messages", Option::default)?,
+            extra: fields.extra()?,
+        })
+    }
 }
 
 
@@ -151,11 +349,596 @@ pub struct ApiFulfillment{
 /**
 * API.ai context object representation.
 */
-#[derive(Serialize,Deserialize)]
+#[derive(Clone,Default)]
 pub struct ApiContext{
     pub name: String,
-    pub parameters: HashMap<String, String>,
-    pub lifespan: Option<i32>
+    pub parameters: Parameters,
+    pub lifespan: Option<i32>,
+    pub extra: Map<String, Value>
+}
+
+impl Serialize for ApiContext {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("name", &self.name)?;
+        fields.field("parameters", &self.parameters)?;
+        fields.field("lifespan", &self.lifespan)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for ApiContext {
+    fn deserialize<D>(deserializer: D) -> Result<ApiContext, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::new("context", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(ApiContext{
+            name: fields.required("name")?,
+            parameters: fields.optional("parameters", Parameters::new)?,
+            lifespan: fields.optional("lifespan", Option::default)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* An agent entity: a named list of values, each with the synonyms that match it.
+*
+* The id is assigned by api.ai when the entity is created, so it is left out of the request when
+* None.
+*/
+#[derive(Clone,Default)]
+pub struct Entity{
+    pub id: Option<String>,
+    pub name: String,
+    pub entries: Vec<EntityEntry>,
+    /**
+    * Whether entries are matched as whole values rather than by their synonyms
+    *
+    */
+    pub is_enum: bool,
+    /**
+    * Whether api.ai may match values that aren't listed but look like the ones that are
+    *
+    */
+    pub automated_expansion: bool,
+    /**
+    * Fields that aren't modelled above
+    */
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for Entity {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.optional("id", &self.id)?;
+        fields.field("name", &self.name)?;
+        fields.field("entries", &self.entries)?;
+        fields.field("isEnum", &self.is_enum)?;
+        fields.field("automatedExpansion", &self.automated_expansion)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for Entity {
+    fn deserialize<D>(deserializer: D) -> Result<Entity, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::new("entity", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(Entity{
+            id: fields.optional("id", Option::default)?,
+            name: fields.required("name")?,
+            entries: fields.optional("entries", Vec::new)?,
+            is_enum: fields.optional("isEnum", bool::default)?,
+            automated_expansion: fields.optional("automatedExpansion", bool::default)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+/**
+* A reference value of an entity and its synonyms
+*
+*/
+#[derive(Clone,Default)]
+pub struct EntityEntry{
+    pub value: String,
+    pub synonyms: Vec<String>,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for EntityEntry {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("value", &self.value)?;
+        fields.field("synonyms", &self.synonyms)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for EntityEntry {
+    fn deserialize<D>(deserializer: D) -> Result<EntityEntry, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::new("entity entry", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(EntityEntry{
+            value: fields.required("value")?,
+            synonyms: fields.optional("synonyms", Vec::new)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+/**
+* An entity as listed by the entities endpoint, with a preview of its entries instead of the
+* entries themselves
+*/
+#[derive(Clone,Default)]
+pub struct EntitySummary{
+    pub id: String,
+    pub name: String,
+    pub count: u32,
+    pub preview: String,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for EntitySummary {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("id", &self.id)?;
+        fields.field("name", &self.name)?;
+        fields.field("count", &self.count)?;
+        fields.field("preview", &self.preview)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for EntitySummary {
+    fn deserialize<D>(deserializer: D) -> Result<EntitySummary, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::new("entity summary", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(EntitySummary{
+            id: fields.required("id")?,
+            name: fields.required("name")?,
+            count: fields.optional("count", u32::default)?,
+            preview: fields.optional("preview", String::new)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+/**
+* Entity entries that apply to a single session, added to (or replacing) those of the agent entity
+* with the same name. Used to match values that only make sense for one user, such as their saved
+* addresses or contacts.
+*
+* The session id can be left empty when the entities are sent inline with a query.
+*/
+#[derive(Clone,Default)]
+pub struct UserEntity{
+    pub session_id: String,
+    pub name: String,
+    /**
+    * Whether the entries extend the agent entity's entries rather than replace them
+    *
+    */
+    pub extend: bool,
+    pub entries: Vec<EntityEntry>,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for UserEntity {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        if !self.session_id.is_empty() {
+            fields.field("sessionId", &self.session_id)?;
+        }
+        fields.field("name", &self.name)?;
+        fields.field("extend", &self.extend)?;
+        fields.field("entries", &self.entries)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for UserEntity {
+    fn deserialize<D>(deserializer: D) -> Result<UserEntity, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::new("user entity", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(UserEntity{
+            session_id: fields.optional("sessionId", String::new)?,
+            name: fields.required("name")?,
+            extend: fields.optional("extend", bool::default)?,
+            entries: fields.required("entries")?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* An agent intent: the phrases that trigger it and the response it produces.
+*
+* The id is assigned by api.ai when the intent is created, so it is left out of the request when
+* None. Like the response structs, the intent types keep the fields they don't model in `extra`
+* so that an exported intent is sent back unchanged.
+*/
+#[derive(Clone,Default)]
+pub struct Intent{
+    pub id: Option<String>,
+    pub name: String,
+    /**
+    * Whether machine learning is used to match queries to the intent
+    *
+    */
+    pub auto: bool,
+    /**
+    * Names of the input contexts that must be active for the intent to match
+    *
+    */
+    pub contexts: Vec<String>,
+    pub templates: Vec<String>,
+    pub user_says: Vec<UserSays>,
+    pub responses: Vec<IntentResponse>,
+    pub priority: Option<i64>,
+    pub webhook_used: bool,
+    pub webhook_for_slot_filling: bool,
+    pub fallback_intent: bool,
+    pub events: Vec<IntentEvent>,
+    /**
+    * Fields that aren't modelled above, such as `lastUpdate`
+    */
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for Intent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.optional("id", &self.id)?;
+        fields.field("name", &self.name)?;
+        fields.field("auto", &self.auto)?;
+        fields.field("contexts", &self.contexts)?;
+        fields.field("templates", &self.templates)?;
+        fields.field("userSays", &self.user_says)?;
+        fields.field("responses", &self.responses)?;
+        fields.optional("priority", &self.priority)?;
+        fields.field("webhookUsed", &self.webhook_used)?;
+        fields.field("webhookForSlotFilling", &self.webhook_for_slot_filling)?;
+        fields.field("fallbackIntent", &self.fallback_intent)?;
+        fields.field("events", &self.events)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for Intent {
+    fn deserialize<D>(deserializer: D) -> Result<Intent, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::new("intent", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(Intent{
+            id: fields.optional("id", Option::default)?,
+            name: fields.required("name")?,
+            auto: fields.optional("auto", bool::default)?,
+            contexts: fields.optional("contexts", Vec::new)?,
+            templates: fields.optional("templates", Vec::new)?,
+            user_says: fields.optional("userSays", Vec::new)?,
+            responses: fields.optional("responses", Vec::new)?,
+            priority: fields.optional("priority", Option::default)?,
+            webhook_used: fields.optional("webhookUsed", bool::default)?,
+            webhook_for_slot_filling: fields.optional("webhookForSlotFilling", bool::default)?,
+            fallback_intent: fields.optional("fallbackIntent", bool::default)?,
+            events: fields.optional("events", Vec::new)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+/**
+* An example phrase for an intent, split into parts so that entities can be annotated
+*
+*/
+#[derive(Clone,Default)]
+pub struct UserSays{
+    pub id: Option<String>,
+    pub data: Vec<UserSaysPart>,
+    pub is_template: bool,
+    /**
+    * How many times the phrase has been added to the intent
+    *
+    */
+    pub count: u32,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for UserSays {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.optional("id", &self.id)?;
+        fields.field("data", &self.data)?;
+        fields.field("isTemplate", &self.is_template)?;
+        fields.field("count", &self.count)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for UserSays {
+    fn deserialize<D>(deserializer: D) -> Result<UserSays, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::new("user says", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(UserSays{
+            id: fields.optional("id", Option::default)?,
+            data: fields.required("data")?,
+            is_template: fields.optional("isTemplate", bool::default)?,
+            count: fields.optional("count", u32::default)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+/**
+* A piece of an example phrase. Parts annotated with an entity carry the entity in `meta`
+* (e.g. `@sys.date`) and the parameter name in `alias`.
+*/
+#[derive(Clone,Default)]
+pub struct UserSaysPart{
+    pub text: String,
+    pub alias: Option<String>,
+    pub meta: Option<String>,
+    pub user_defined: Option<bool>,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for UserSaysPart {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("text", &self.text)?;
+        fields.optional("alias", &self.alias)?;
+        fields.optional("meta", &self.meta)?;
+        fields.optional("userDefined", &self.user_defined)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for UserSaysPart {
+    fn deserialize<D>(deserializer: D) -> Result<UserSaysPart, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::new("user says part", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(UserSaysPart{
+            text: fields.required("text")?,
+            alias: fields.optional("alias", Option::default)?,
+            meta: fields.optional("meta", Option::default)?,
+            user_defined: fields.optional("userDefined", Option::default)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+/**
+* What an intent does when it matches: the action, the contexts it sets and the messages sent back
+*
+*/
+#[derive(Clone,Default)]
+pub struct IntentResponse{
+    pub action: Option<String>,
+    pub reset_contexts: bool,
+    pub affected_contexts: Vec<ApiContext>,
+    pub parameters: Vec<IntentParameter>,
+    pub messages: Vec<ApiMessage>,
+    /**
+    * Fields that aren't modelled above, such as `defaultResponsePlatforms`
+    */
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for IntentResponse {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.optional("action", &self.action)?;
+        fields.field("resetContexts", &self.reset_contexts)?;
+        fields.field("affectedContexts", &self.affected_contexts)?;
+        fields.field("parameters", &self.parameters)?;
+        fields.field("messages", &self.messages)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for IntentResponse {
+    fn deserialize<D>(deserializer: D) -> Result<IntentResponse, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::new("intent response", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(IntentResponse{
+            action: fields.optional("action", Option::default)?,
+            reset_contexts: fields.optional("resetContexts", bool::default)?,
+            affected_contexts: fields.optional("affectedContexts", Vec::new)?,
+            parameters: fields.optional("parameters", Vec::new)?,
+            messages: fields.optional("messages", Vec::new)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+/**
+* A parameter extracted by an intent, e.g. `{ name: "city", dataType: "@sys.geo-city", value: "$city" }`
+*
+*/
+#[derive(Clone,Default)]
+pub struct IntentParameter{
+    pub id: Option<String>,
+    pub name: String,
+    pub data_type: String,
+    pub value: String,
+    pub default_value: Option<String>,
+    pub required: bool,
+    pub is_list: bool,
+    /**
+    * Questions asked to fill the parameter when it is required but missing. Left out when empty,
+    * as api.ai does for parameters that aren't required.
+    */
+    pub prompts: Vec<String>,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for IntentParameter {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.optional("id", &self.id)?;
+        fields.field("name", &self.name)?;
+        fields.field("dataType", &self.data_type)?;
+        fields.field("value", &self.value)?;
+        fields.optional("defaultValue", &self.default_value)?;
+        fields.field("required", &self.required)?;
+        fields.field("isList", &self.is_list)?;
+        if !self.prompts.is_empty() {
+            fields.field("prompts", &self.prompts)?;
+        }
+        fields.end()
+    }
+}
+
+impl Deserialize for IntentParameter {
+    fn deserialize<D>(deserializer: D) -> Result<IntentParameter, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::new("intent parameter", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(IntentParameter{
+            id: fields.optional("id", Option::default)?,
+            name: fields.required("name")?,
+            data_type: fields.required("dataType")?,
+            value: fields.required("value")?,
+            default_value: fields.optional("defaultValue", Option::default)?,
+            required: fields.optional("required", bool::default)?,
+            is_list: fields.optional("isList", bool::default)?,
+            prompts: fields.optional("prompts", Vec::new)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+/**
+* An event that triggers an intent
+*
+*/
+#[derive(Clone,Default)]
+pub struct IntentEvent{
+    pub name: String,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for IntentEvent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("name", &self.name)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for IntentEvent {
+    fn deserialize<D>(deserializer: D) -> Result<IntentEvent, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::new("intent event", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(IntentEvent{
+            name: fields.required("name")?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+/**
+* An intent as listed by the intents endpoint
+*
+*/
+#[derive(Clone,Default)]
+pub struct IntentSummary{
+    pub id: String,
+    pub name: String,
+    pub context_in: Vec<String>,
+    pub context_out: Vec<ApiContext>,
+    pub events: Vec<IntentEvent>,
+    pub parameters: Vec<IntentParameter>,
+    pub actions: Vec<String>,
+    pub priority: Option<i64>,
+    pub fallback_intent: bool,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for IntentSummary {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("id", &self.id)?;
+        fields.field("name", &self.name)?;
+        fields.field("contextIn", &self.context_in)?;
+        fields.field("contextOut", &self.context_out)?;
+        fields.field("events", &self.events)?;
+        fields.field("parameters", &self.parameters)?;
+        fields.field("actions", &self.actions)?;
+        fields.optional("priority", &self.priority)?;
+        fields.field("fallbackIntent", &self.fallback_intent)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for IntentSummary {
+    fn deserialize<D>(deserializer: D) -> Result<IntentSummary, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::new("intent summary", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(IntentSummary{
+            id: fields.required("id")?,
+            name: fields.required("name")?,
+            context_in: fields.optional("contextIn", Vec::new)?,
+            context_out: fields.optional("contextOut", Vec::new)?,
+            events: fields.optional("events", Vec::new)?,
+            parameters: fields.optional("parameters", Vec::new)?,
+            actions: fields.optional("actions", Vec::new)?,
+            priority: fields.optional("priority", Option::default)?,
+            fallback_intent: fields.optional("fallbackIntent", bool::default)?,
+            extra: fields.extra()?,
+        })
+    }
 }
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
@@ -163,10 +946,10 @@ pub struct ApiContext{
 /**
 * An ApiEvent has a name and may have a list of parameters under the label 'data'.
 */
-#[derive(Serialize,Deserialize)]
+#[derive(Clone,Serialize,Deserialize)]
 pub struct ApiEvent{
     pub name: String,
-    pub data: Option<HashMap<String,String>>
+    pub data: Option<Parameters>
 }
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
@@ -175,7 +958,7 @@ pub struct ApiEvent{
 * ApiRequest is a structure that encapsulates an api.ai request object
 *
 */
-#[derive(Serialize,Deserialize)]
+#[derive(Clone,Serialize,Deserialize)]
 pub struct ApiRequest{
     /**
     *  queryis a simple string that holds the query payload for api.ai requests.
@@ -190,7 +973,52 @@ pub struct ApiRequest{
     #[serde( rename = "sessionId", skip_serializing_if="String::is_empty", default) ]
     pub session_id: String,
     pub lang: Language,
-    pub contexts: Vec<ApiContext>
+    pub contexts: Vec<ApiContext>,
+    /**
+    * User entities that apply to this query's session, sent inline instead of through the
+    * userEntities endpoint
+    */
+    #[serde(skip_serializing_if="Vec::is_empty", default)]
+    pub entities: Vec<UserEntity>,
+    /**
+    * If true, the session's contexts are cleared before the query is matched
+    */
+    #[serde(rename = "resetContexts", skip_serializing_if="Option::is_none", default)]
+    pub reset_contexts: Option<bool>,
+    /**
+    * The user's time zone from the tz database, e.g. `Europe/Paris`, used to resolve dates and
+    * times such as "tomorrow at 9". api.ai assumes the agent's time zone if it isn't given.
+    */
+    #[serde(skip_serializing_if="Option::is_none", default)]
+    pub timezone: Option<String>,
+    #[serde(skip_serializing_if="Option::is_none", default)]
+    pub location: Option<ApiLocation>,
+    /**
+    * The request that came in from an integration, passed on to the webhook
+    */
+    #[serde(rename = "originalRequest", skip_serializing_if="Option::is_none", default)]
+    pub original_request: Option<ApiOriginalRequest>
+}
+
+/**
+* Where the user is, as latitude and longitude in degrees
+*
+*/
+#[derive(Clone,Debug,PartialEq,Serialize,Deserialize)]
+pub struct ApiLocation{
+    pub latitude: f64,
+    pub longitude: f64
+}
+
+/**
+* The original request from an integration such as `facebook` or `slack`
+*
+*/
+#[derive(Clone,Serialize,Deserialize)]
+pub struct ApiOriginalRequest{
+    pub source: String,
+    #[serde(skip_serializing_if="Option::is_none", default)]
+    pub data: Option<Value>
 }
 
 /**
@@ -205,7 +1033,12 @@ impl Default for ApiRequest {
             event: Option::None,
             session_id: Uuid::new_v4().hyphenated().to_string(),
             lang: Language::English,
-            contexts: Vec::new()
+            contexts: Vec::new(),
+            entities: Vec::new(),
+            reset_contexts: Option::None,
+            timezone: Option::None,
+            location: Option::None,
+            original_request: Option::None
         }
     }
 
@@ -214,32 +1047,832 @@ impl Default for ApiRequest {
 /**
 * ApiMesages contain various types of message - text, images, buttons and more.
 *
+* Messages are told apart by their `type`: a number for the generic messages (0 to 4) and a name
+* such as `simple_response` for the Google Assistant ones. Messages of a type this crate doesn't
+* know, or that can't be read as the type they claim to be, are kept as they are in `Unknown`
+* and written back out unchanged.
 */
-#[derive(Serialize,Deserialize)]
-#[serde(untagged)]
+#[derive(Clone,Debug,PartialEq)]
 pub enum ApiMessage{
-
-    Text {
-        speech: String,
-        #[serde(rename="type")]
-        message_type: i32
-    },
-    Image {
-        #[serde(rename="imageUrl")]
-        image_url: String,
-        #[serde(rename="type")]
-        message_type: i32
-    }
+    Text(TextResponse),
+    Card(Card),
+    QuickReplies(QuickReplies),
+    Image(ImageResponse),
+    CustomPayload(CustomPayload),
+    SimpleResponse(SimpleResponse),
+    BasicCard(BasicCard),
+    SuggestionChips(SuggestionChips),
+    LinkOutChip(LinkOutChip),
+    ListCard(ListCard),
+    CarouselCard(CarouselCard),
+    Unknown(Value),
 }
 
 impl ApiMessage {
 
     pub fn new_text(speech: String) -> ApiMessage {
-        ApiMessage::Text{speech: speech, message_type: 0}
+        ApiMessage::Text(TextResponse{speech: vec![speech], ..Default::default()})
     }
 
     pub fn new_image(image_url: String) -> ApiMessage {
-        ApiMessage::Image{image_url: image_url, message_type: 3}
+        ApiMessage::Image(ImageResponse{image_url: image_url, ..Default::default()})
+    }
+
+    /**
+    * The platform the message is meant for, e.g. `facebook` or `google`. None means any.
+    */
+    pub fn platform(&self) -> Option<&str> {
+        let platform = match *self {
+            ApiMessage::Text(ref message) => &message.platform,
+            ApiMessage::Card(ref message) => &message.platform,
+            ApiMessage::QuickReplies(ref message) => &message.platform,
+            ApiMessage::Image(ref message) => &message.platform,
+            ApiMessage::CustomPayload(ref message) => &message.platform,
+            ApiMessage::SimpleResponse(ref message) => &message.platform,
+            ApiMessage::BasicCard(ref message) => &message.platform,
+            ApiMessage::SuggestionChips(ref message) => &message.platform,
+            ApiMessage::LinkOutChip(ref message) => &message.platform,
+            ApiMessage::ListCard(ref message) => &message.platform,
+            ApiMessage::CarouselCard(ref message) => &message.platform,
+            ApiMessage::Unknown(ref value) => return value.get("platform").and_then(Value::as_str),
+        };
+
+        platform.as_ref().map(String::as_str)
+    }
+
+    fn from_value(value: Value) -> Result<ApiMessage, serde_json::Error> {
+
+        let message_type = match value.get("type") {
+            Option::Some(&Value::Number(ref number)) => number.to_string(),
+            Option::Some(&Value::String(ref name)) => name.clone(),
+            _ => String::new()
+        };
+
+        // The type is written back by to_value, so it isn't kept in the message's extra fields
+        let mut message = value.clone();
+        if let Value::Object(ref mut fields) = message {
+            fields.remove("type");
+        }
+
+        let parsed = match message_type.as_str() {
+            "0" => serde_json::from_value(message).map(ApiMessage::Text),
+            "1" => serde_json::from_value(message).map(ApiMessage::Card),
+            "2" => serde_json::from_value(message).map(ApiMessage::QuickReplies),
+            "3" => serde_json::from_value(message).map(ApiMessage::Image),
+            "4" => serde_json::from_value(message).map(ApiMessage::CustomPayload),
+            "simple_response" => serde_json::from_value(message).map(ApiMessage::SimpleResponse),
+            "basic_card" => serde_json::from_value(message).map(ApiMessage::BasicCard),
+            "suggestion_chips" => serde_json::from_value(message).map(ApiMessage::SuggestionChips),
+            "link_out_chip" => serde_json::from_value(message).map(ApiMessage::LinkOutChip),
+            "list_card" => serde_json::from_value(message).map(ApiMessage::ListCard),
+            "carousel_card" => serde_json::from_value(message).map(ApiMessage::CarouselCard),
+            _ => return Result::Ok(ApiMessage::Unknown(value))
+        };
+
+        // Outside strict mode a message that doesn't have the shape of its type is kept as it was sent
+        match parsed {
+            Result::Err(_) if !STRICT => Result::Ok(ApiMessage::Unknown(value)),
+            parsed => parsed
+        }
+    }
+
+    fn to_value(&self) -> Result<Value, serde_json::Error> {
+
+        let (message, message_type) = match *self {
+            ApiMessage::Text(ref message) => (serde_json::to_value(message)?, Value::from(0)),
+            ApiMessage::Card(ref message) => (serde_json::to_value(message)?, Value::from(1)),
+            ApiMessage::QuickReplies(ref message) => (serde_json::to_value(message)?, Value::from(2)),
+            ApiMessage::Image(ref message) => (serde_json::to_value(message)?, Value::from(3)),
+            ApiMessage::CustomPayload(ref message) => (serde_json::to_value(message)?, Value::from(4)),
+            ApiMessage::SimpleResponse(ref message) => (serde_json::to_value(message)?, Value::from("simple_response")),
+            ApiMessage::BasicCard(ref message) => (serde_json::to_value(message)?, Value::from("basic_card")),
+            ApiMessage::SuggestionChips(ref message) => (serde_json::to_value(message)?, Value::from("suggestion_chips")),
+            ApiMessage::LinkOutChip(ref message) => (serde_json::to_value(message)?, Value::from("link_out_chip")),
+            ApiMessage::ListCard(ref message) => (serde_json::to_value(message)?, Value::from("list_card")),
+            ApiMessage::CarouselCard(ref message) => (serde_json::to_value(message)?, Value::from("carousel_card")),
+            ApiMessage::Unknown(ref value) => return Result::Ok(value.clone()),
+        };
+
+        let mut message = message;
+        if let Value::Object(ref mut fields) = message {
+            fields.insert(String::from("type"), message_type);
+        }
+
+        Result::Ok(message)
+    }
+}
+
+impl Serialize for ApiMessage {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        self.to_value()
+            .map_err(ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl Deserialize for ApiMessage {
+    fn deserialize<D>(deserializer: D) -> Result<ApiMessage, D::Error>
+        where D: Deserializer
+    {
+        let value: Value = Deserialize::deserialize(deserializer)?;
+        ApiMessage::from_value(value).map_err(de::Error::custom)
+    }
+}
+
+/**
+* Text response (type 0)
+*
+* Query responses give `speech` as a string while intents hold a list of variants to pick from.
+* Both are read into `speech`; `speech_list` records which was used so that a message is written
+* back out the same way. More or fewer than one variant is always written as a list.
+*/
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct TextResponse{
+    pub speech: Vec<String>,
+    pub speech_list: bool,
+    pub platform: Option<String>,
+    pub extra: Map<String, Value>,
+}
+
+impl TextResponse {
+
+    /**
+    * The first speech variant, which is the only one in a query response
+    */
+    pub fn first_speech(&self) -> Option<&str> {
+        self.speech.first().map(String::as_str)
+    }
+}
+
+impl Serialize for TextResponse {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        if self.speech_list || self.speech.len() != 1 {
+            fields.field("speech", &self.speech)?;
+        } else {
+            fields.field("speech", &self.speech[0])?;
+        }
+        fields.optional("platform", &self.platform)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for TextResponse {
+    fn deserialize<D>(deserializer: D) -> Result<TextResponse, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::exact("text message", Deserialize::deserialize(deserializer)?)?;
+
+        let (speech, speech_list) = match fields.required("speech")? {
+            Value::String(speech) => (vec![speech], false),
+            Value::Array(variants) => {
+                let speech = variants.into_iter()
+                    .map(|variant| match variant {
+                        Value::String(speech) => Result::Ok(speech),
+                        _ => Result::Err(de::Error::custom("speech variants should be strings"))
+                    })
+                    .collect::<Result<Vec<String>, D::Error>>()?;
+                (speech, true)
+            },
+            _ => return Result::Err(de::Error::custom("speech should be a string or a list of strings"))
+        };
+
+        Result::Ok(TextResponse{
+            speech: speech,
+            speech_list: speech_list,
+            platform: fields.optional("platform", Option::default)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+/**
+* Card (type 1)
+*
+*/
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct Card{
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub image_url: Option<String>,
+    pub buttons: Vec<CardButton>,
+    pub platform: Option<String>,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for Card {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("title", &self.title)?;
+        fields.optional("subtitle", &self.subtitle)?;
+        fields.optional("imageUrl", &self.image_url)?;
+        fields.field("buttons", &self.buttons)?;
+        fields.optional("platform", &self.platform)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for Card {
+    fn deserialize<D>(deserializer: D) -> Result<Card, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::exact("card", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(Card{
+            title: fields.optional("title", String::new)?,
+            subtitle: fields.optional("subtitle", Option::default)?,
+            image_url: fields.optional("imageUrl", Option::default)?,
+            buttons: fields.optional("buttons", Vec::new)?,
+            platform: fields.optional("platform", Option::default)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct CardButton{
+    pub text: String,
+    /**
+    * Text sent back as a query, or a URL to open, when the button is pressed
+    */
+    pub postback: Option<String>,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for CardButton {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("text", &self.text)?;
+        fields.optional("postback", &self.postback)?;
+        fields.end()
     }
+}
+
+impl Deserialize for CardButton {
+    fn deserialize<D>(deserializer: D) -> Result<CardButton, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::exact("card button", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(CardButton{
+            text: fields.required("text")?,
+            postback: fields.optional("postback", Option::default)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+/**
+* Quick replies (type 2)
+*
+*/
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct QuickReplies{
+    pub title: Option<String>,
+    pub replies: Vec<String>,
+    pub platform: Option<String>,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for QuickReplies {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.optional("title", &self.title)?;
+        fields.field("replies", &self.replies)?;
+        fields.optional("platform", &self.platform)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for QuickReplies {
+    fn deserialize<D>(deserializer: D) -> Result<QuickReplies, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::exact("quick replies", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(QuickReplies{
+            title: fields.optional("title", Option::default)?,
+            replies: fields.optional("replies", Vec::new)?,
+            platform: fields.optional("platform", Option::default)?,
+            extra: fields.extra()?,
+        })
+    }
+}
 
+/**
+* Image (type 3)
+*
+*/
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct ImageResponse{
+    pub image_url: String,
+    pub platform: Option<String>,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for ImageResponse {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("imageUrl", &self.image_url)?;
+        fields.optional("platform", &self.platform)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for ImageResponse {
+    fn deserialize<D>(deserializer: D) -> Result<ImageResponse, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::exact("image message", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(ImageResponse{
+            image_url: fields.required("imageUrl")?,
+            platform: fields.optional("platform", Option::default)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+/**
+* Custom payload (type 4), passed through to the platform as is
+*
+*/
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct CustomPayload{
+    pub payload: Value,
+    pub platform: Option<String>,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for CustomPayload {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("payload", &self.payload)?;
+        fields.optional("platform", &self.platform)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for CustomPayload {
+    fn deserialize<D>(deserializer: D) -> Result<CustomPayload, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::exact("custom payload", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(CustomPayload{
+            payload: fields.optional("payload", Value::default)?,
+            platform: fields.optional("platform", Option::default)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+/**
+* Google Assistant simple response (`simple_response`)
+*
+*/
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct SimpleResponse{
+    pub text_to_speech: Option<String>,
+    pub ssml: Option<String>,
+    pub display_text: Option<String>,
+    pub platform: Option<String>,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for SimpleResponse {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.optional("textToSpeech", &self.text_to_speech)?;
+        fields.optional("ssml", &self.ssml)?;
+        fields.optional("displayText", &self.display_text)?;
+        fields.optional("platform", &self.platform)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for SimpleResponse {
+    fn deserialize<D>(deserializer: D) -> Result<SimpleResponse, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::exact("simple response", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(SimpleResponse{
+            text_to_speech: fields.optional("textToSpeech", Option::default)?,
+            ssml: fields.optional("ssml", Option::default)?,
+            display_text: fields.optional("displayText", Option::default)?,
+            platform: fields.optional("platform", Option::default)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+/**
+* Google Assistant basic card (`basic_card`)
+*
+*/
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct BasicCard{
+    pub title: Option<String>,
+    pub subtitle: Option<String>,
+    pub formatted_text: Option<String>,
+    pub image: Option<GoogleImage>,
+    pub buttons: Vec<GoogleButton>,
+    pub platform: Option<String>,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for BasicCard {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.optional("title", &self.title)?;
+        fields.optional("subtitle", &self.subtitle)?;
+        fields.optional("formattedText", &self.formatted_text)?;
+        fields.optional("image", &self.image)?;
+        fields.field("buttons", &self.buttons)?;
+        fields.optional("platform", &self.platform)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for BasicCard {
+    fn deserialize<D>(deserializer: D) -> Result<BasicCard, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::exact("basic card", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(BasicCard{
+            title: fields.optional("title", Option::default)?,
+            subtitle: fields.optional("subtitle", Option::default)?,
+            formatted_text: fields.optional("formattedText", Option::default)?,
+            image: fields.optional("image", Option::default)?,
+            buttons: fields.optional("buttons", Vec::new)?,
+            platform: fields.optional("platform", Option::default)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct GoogleImage{
+    pub url: String,
+    pub accessibility_text: String,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for GoogleImage {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("url", &self.url)?;
+        fields.field("accessibilityText", &self.accessibility_text)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for GoogleImage {
+    fn deserialize<D>(deserializer: D) -> Result<GoogleImage, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::exact("image", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(GoogleImage{
+            url: fields.required("url")?,
+            accessibility_text: fields.optional("accessibilityText", String::new)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct GoogleButton{
+    pub title: String,
+    pub open_url_action: OpenUrlAction,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for GoogleButton {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("title", &self.title)?;
+        fields.field("openUrlAction", &self.open_url_action)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for GoogleButton {
+    fn deserialize<D>(deserializer: D) -> Result<GoogleButton, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::exact("button", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(GoogleButton{
+            title: fields.required("title")?,
+            open_url_action: fields.required("openUrlAction")?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct OpenUrlAction{
+    pub url: String,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for OpenUrlAction {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("url", &self.url)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for OpenUrlAction {
+    fn deserialize<D>(deserializer: D) -> Result<OpenUrlAction, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::exact("open url action", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(OpenUrlAction{
+            url: fields.required("url")?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+/**
+* Google Assistant suggestion chips (`suggestion_chips`)
+*
+*/
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct SuggestionChips{
+    pub suggestions: Vec<Suggestion>,
+    pub platform: Option<String>,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for SuggestionChips {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("suggestions", &self.suggestions)?;
+        fields.optional("platform", &self.platform)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for SuggestionChips {
+    fn deserialize<D>(deserializer: D) -> Result<SuggestionChips, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::exact("suggestion chips", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(SuggestionChips{
+            suggestions: fields.optional("suggestions", Vec::new)?,
+            platform: fields.optional("platform", Option::default)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct Suggestion{
+    pub title: String,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for Suggestion {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("title", &self.title)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for Suggestion {
+    fn deserialize<D>(deserializer: D) -> Result<Suggestion, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::exact("suggestion", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(Suggestion{
+            title: fields.required("title")?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+/**
+* Google Assistant link out chip (`link_out_chip`)
+*
+*/
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct LinkOutChip{
+    pub destination_name: String,
+    pub url: String,
+    pub platform: Option<String>,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for LinkOutChip {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("destinationName", &self.destination_name)?;
+        fields.field("url", &self.url)?;
+        fields.optional("platform", &self.platform)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for LinkOutChip {
+    fn deserialize<D>(deserializer: D) -> Result<LinkOutChip, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::exact("link out chip", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(LinkOutChip{
+            destination_name: fields.required("destinationName")?,
+            url: fields.required("url")?,
+            platform: fields.optional("platform", Option::default)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+/**
+* Google Assistant list (`list_card`)
+*
+*/
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct ListCard{
+    pub title: Option<String>,
+    pub items: Vec<SelectItem>,
+    pub platform: Option<String>,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for ListCard {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.optional("title", &self.title)?;
+        fields.field("items", &self.items)?;
+        fields.optional("platform", &self.platform)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for ListCard {
+    fn deserialize<D>(deserializer: D) -> Result<ListCard, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::exact("list card", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(ListCard{
+            title: fields.optional("title", Option::default)?,
+            items: fields.optional("items", Vec::new)?,
+            platform: fields.optional("platform", Option::default)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+/**
+* Google Assistant carousel (`carousel_card`)
+*
+*/
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct CarouselCard{
+    pub items: Vec<SelectItem>,
+    pub platform: Option<String>,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for CarouselCard {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("items", &self.items)?;
+        fields.optional("platform", &self.platform)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for CarouselCard {
+    fn deserialize<D>(deserializer: D) -> Result<CarouselCard, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::exact("carousel card", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(CarouselCard{
+            items: fields.optional("items", Vec::new)?,
+            platform: fields.optional("platform", Option::default)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+/**
+* An item of a list or carousel. Selecting it sends `option_info.key` back to the agent.
+*
+*/
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct SelectItem{
+    pub option_info: OptionInfo,
+    pub title: String,
+    pub description: Option<String>,
+    pub image: Option<GoogleImage>,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for SelectItem {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("optionInfo", &self.option_info)?;
+        fields.field("title", &self.title)?;
+        fields.optional("description", &self.description)?;
+        fields.optional("image", &self.image)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for SelectItem {
+    fn deserialize<D>(deserializer: D) -> Result<SelectItem, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::exact("select item", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(SelectItem{
+            option_info: fields.required("optionInfo")?,
+            title: fields.required("title")?,
+            description: fields.optional("description", Option::default)?,
+            image: fields.optional("image", Option::default)?,
+            extra: fields.extra()?,
+        })
+    }
+}
+
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct OptionInfo{
+    pub key: String,
+    pub synonyms: Vec<String>,
+    pub extra: Map<String, Value>,
+}
+
+impl Serialize for OptionInfo {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut fields = FieldWriter::new(serializer.serialize_map(Option::None)?, &self.extra);
+        fields.field("key", &self.key)?;
+        fields.field("synonyms", &self.synonyms)?;
+        fields.end()
+    }
+}
+
+impl Deserialize for OptionInfo {
+    fn deserialize<D>(deserializer: D) -> Result<OptionInfo, D::Error>
+        where D: Deserializer
+    {
+        let mut fields = Fields::exact("option info", Deserialize::deserialize(deserializer)?)?;
+
+        Result::Ok(OptionInfo{
+            key: fields.required("key")?,
+            synonyms: fields.optional("synonyms", Vec::new)?,
+            extra: fields.extra()?,
+        })
+    }
 }
@@ -0,0 +1,242 @@
+use chrono::{self, DateTime, NaiveDate, NaiveDateTime, NaiveTime, UTC};
+use serde::de::{self, Deserialize, Deserializer};
+use serde_json::Value;
+
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* `@sys.date`, e.g. `2017-03-10`
+*
+*/
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct SysDate(pub NaiveDate);
+
+impl SysDate {
+    pub fn parse(text: &str) -> Option<SysDate> {
+        parse_date(text).map(SysDate)
+    }
+}
+
+/**
+* `@sys.time`, e.g. `14:30:00`
+*
+*/
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct SysTime(pub NaiveTime);
+
+impl SysTime {
+    pub fn parse(text: &str) -> Option<SysTime> {
+        parse_time(text).map(SysTime)
+    }
+}
+
+/**
+* `@sys.date-time`, e.g. `2017-03-10T14:30:00Z`. Values without a zone are taken to be UTC.
+*
+*/
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct SysDateTime(pub DateTime<UTC>);
+
+impl SysDateTime {
+    pub fn parse(text: &str) -> Option<SysDateTime> {
+        if let Result::Ok(date_time) = DateTime::parse_from_rfc3339(text) {
+            return Option::Some(SysDateTime(date_time.with_timezone(&UTC)));
+        }
+
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S").ok()
+            .map(|date_time| SysDateTime(DateTime::from_utc(date_time, UTC)))
+    }
+}
+
+/**
+* `@sys.date-period`, e.g. `2017-03-01/2017-03-31`. Both ends are included.
+*
+*/
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct DatePeriod {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl DatePeriod {
+
+    pub fn parse(text: &str) -> Option<DatePeriod> {
+        split_period(text).and_then(|(start, end)| {
+            match (parse_date(start), parse_date(end)) {
+                (Option::Some(start), Option::Some(end)) => Option::Some(DatePeriod{ start: start, end: end }),
+                _ => Option::None
+            }
+        })
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+}
+
+/**
+* `@sys.time-period`, e.g. `14:00:00/16:00:00`
+*
+*/
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct TimePeriod {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl TimePeriod {
+    pub fn parse(text: &str) -> Option<TimePeriod> {
+        split_period(text).and_then(|(start, end)| {
+            match (parse_time(start), parse_time(end)) {
+                (Option::Some(start), Option::Some(end)) => Option::Some(TimePeriod{ start: start, end: end }),
+                _ => Option::None
+            }
+        })
+    }
+}
+
+/**
+* `@sys.number`, `@sys.cardinal` and `@sys.ordinal`, which are sent either as a JSON number or as
+* a string holding one
+*/
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct SysNumber(pub f64);
+
+/**
+* `@sys.unit-currency`, e.g. `{"amount": 20, "currency": "USD"}`
+*
+*/
+#[derive(Clone,Debug,PartialEq,Deserialize)]
+pub struct UnitCurrency {
+    #[serde(deserialize_with = "deserialize_amount")]
+    pub amount: f64,
+    pub currency: String,
+}
+
+/**
+* An amount with a unit, as given by `@sys.duration`, `@sys.age`, `@sys.temperature` and the
+* `@sys.unit-*` entities for length, area, volume, weight and speed, e.g.
+* `{"amount": 2, "unit": "h"}`
+*/
+#[derive(Clone,Debug,PartialEq,Deserialize)]
+pub struct UnitAmount {
+    #[serde(deserialize_with = "deserialize_amount")]
+    pub amount: f64,
+    pub unit: String,
+}
+
+impl UnitAmount {
+
+    /**
+    * The length of time given by a `@sys.duration` value. Months and years have no fixed length,
+    * so they, like any unit that isn't a unit of time, give None.
+    */
+    pub fn to_duration(&self) -> Option<chrono::Duration> {
+        let millis_per_unit = match self.unit.as_str() {
+            "ms" => 1.0,
+            "s" => 1000.0,
+            "min" => 60.0 * 1000.0,
+            "h" => 60.0 * 60.0 * 1000.0,
+            "day" => 24.0 * 60.0 * 60.0 * 1000.0,
+            "wk" => 7.0 * 24.0 * 60.0 * 60.0 * 1000.0,
+            _ => return Option::None
+        };
+
+        Option::Some(chrono::Duration::milliseconds((self.amount * millis_per_unit).round() as i64))
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d").ok()
+}
+
+fn parse_time(text: &str) -> Option<NaiveTime> {
+    let text = text.trim();
+    NaiveTime::parse_from_str(text, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(text, "%H:%M"))
+        .ok()
+}
+
+fn split_period(text: &str) -> Option<(&str, &str)> {
+    let mut parts = text.splitn(2, '/');
+    match (parts.next(), parts.next()) {
+        (Option::Some(start), Option::Some(end)) => Option::Some((start, end)),
+        _ => Option::None
+    }
+}
+
+fn number_from_value(value: &Value) -> Option<f64> {
+    match *value {
+        Value::String(ref text) => text.trim().parse().ok(),
+        ref other => other.as_f64()
+    }
+}
+
+fn deserialize_amount<D>(deserializer: D) -> Result<f64, D::Error>
+    where D: Deserializer
+{
+    let value: Value = Deserialize::deserialize(deserializer)?;
+    number_from_value(&value).ok_or_else(|| de::Error::custom(format!("expected a number, got {}", value)))
+}
+
+/**
+* Read a string and decode it with `parse`, naming `expected` in the error if that fails
+*/
+fn deserialize_str<D, T, F>(deserializer: D, expected: &str, parse: F) -> Result<T, D::Error>
+    where D: Deserializer,
+          F: FnOnce(&str) -> Option<T>
+{
+    let text = String::deserialize(deserializer)?;
+    parse(&text).ok_or_else(|| de::Error::custom(format!("expected {}, got {:?}", expected, text)))
+}
+
+impl Deserialize for SysDate {
+    fn deserialize<D>(deserializer: D) -> Result<SysDate, D::Error>
+        where D: Deserializer
+    {
+        deserialize_str(deserializer, "a date", SysDate::parse)
+    }
+}
+
+impl Deserialize for SysTime {
+    fn deserialize<D>(deserializer: D) -> Result<SysTime, D::Error>
+        where D: Deserializer
+    {
+        deserialize_str(deserializer, "a time", SysTime::parse)
+    }
+}
+
+impl Deserialize for SysDateTime {
+    fn deserialize<D>(deserializer: D) -> Result<SysDateTime, D::Error>
+        where D: Deserializer
+    {
+        deserialize_str(deserializer, "a date and time", SysDateTime::parse)
+    }
+}
+
+impl Deserialize for DatePeriod {
+    fn deserialize<D>(deserializer: D) -> Result<DatePeriod, D::Error>
+        where D: Deserializer
+    {
+        deserialize_str(deserializer, "a date period", DatePeriod::parse)
+    }
+}
+
+impl Deserialize for TimePeriod {
+    fn deserialize<D>(deserializer: D) -> Result<TimePeriod, D::Error>
+        where D: Deserializer
+    {
+        deserialize_str(deserializer, "a time period", TimePeriod::parse)
+    }
+}
+
+impl Deserialize for SysNumber {
+    fn deserialize<D>(deserializer: D) -> Result<SysNumber, D::Error>
+        where D: Deserializer
+    {
+        deserialize_amount(deserializer).map(SysNumber)
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read, Write};
+
+use archive::{AgentArchive, ArchiveError};
+
+use serde_json::{self, Value};
+use zip::{ZipArchive, ZipWriter};
+use zip::write::FileOptions;
+
+static AGENT_ZIP: &'static [u8] = include_bytes!("fixtures/agent.zip");
+
+/**
+* Read every file in a zip, parsing the JSON ones so that formatting differences don't matter
+*/
+fn zip_contents(data: &[u8]) -> BTreeMap<String, Value> {
+    let mut zip = ZipArchive::new(Cursor::new(data)).unwrap();
+    let mut contents = BTreeMap::new();
+
+    for index in 0..zip.len() {
+        let mut file = zip.by_index(index).unwrap();
+        if file.name().ends_with('/') {
+            continue;
+        }
+
+        let mut data = Vec::new();
+        file.read_to_end(&mut data).unwrap();
+        contents.insert(String::from(file.name()), serde_json::from_slice(&data).unwrap());
+    }
+
+    contents
+}
+
+fn rewrite(archive: &AgentArchive) -> Vec<u8> {
+    archive.write(Cursor::new(Vec::new())).unwrap().into_inner()
+}
+
+/**
+* Test reading the fixture export into the archive types
+*
+*/
+#[cfg(not(feature = "strict"))]
+#[test]
+fn test_read_agent_archive() {
+    use client::ApiMessage;
+
+    let archive = AgentArchive::read(Cursor::new(AGENT_ZIP)).unwrap();
+
+    assert_eq!(archive.agent.description, "Weather and small talk");
+    assert_eq!(archive.agent.default_timezone.as_ref().unwrap(), "Europe/London");
+    assert_eq!(archive.agent.other["webhook"]["url"].as_str(), Option::Some("https://hooks.example.com/weather"));
+
+    assert_eq!(archive.agent.other["googleAssistant"]["project"].as_str(), Option::Some("weather-bot"));
+
+    assert_eq!(archive.intents.len(), 3);
+    let welcome = archive.intent("Default Welcome Intent").unwrap();
+    assert_eq!(welcome.user_says["de"][0].data[0].text, "hallo");
+    assert_eq!(welcome.user_says["en"][0].data[0].text, "hello");
+    assert_eq!(welcome.user_says["en"][1].extra["updated"], 1510831429);
+    assert_eq!(welcome.intent.extra["lastUpdate"], 1510831429);
+
+    let responses = &welcome.intent.responses[0];
+    assert_eq!(responses.extra["defaultResponsePlatforms"]["google"].as_bool(), Option::Some(true));
+    match responses.messages[0] {
+        ApiMessage::Text(ref text) => {
+            assert_eq!(text.speech[1], "Hi there!");
+            assert!(text.speech_list);
+        },
+        _ => panic!("Expected a text message with speech variants")
+    }
+    match responses.messages[1] {
+        ApiMessage::Text(ref text) => assert_eq!(text.extra["lang"], "de"),
+        _ => panic!("Expected a text message")
+    }
+
+    let weather = archive.intent("weather.forecast").unwrap();
+    assert_eq!(weather.user_says["en"][0].data[1].meta.as_ref().unwrap(), "@sys.geo-city");
+    assert_eq!(weather.intent.responses[0].parameters[0].extra["prompts"][1]["value"], "Welche Stadt?");
+
+    let follow_up = archive.intent("weather.forecast - tomorrow").unwrap();
+    assert_eq!(follow_up.intent.extra["parentId"], "2a7c1d04-3b5e-4f8a-9d61-7e0b8c2f4a15");
+    assert_eq!(follow_up.intent.extra["rootParentId"], "2a7c1d04-3b5e-4f8a-9d61-7e0b8c2f4a15");
+
+    let city = archive.entity("city").unwrap();
+    assert_eq!(city.entries["en"][0].synonyms, vec![String::from("London"), String::from("ldn")]);
+    assert_eq!(city.entity.extra["isOverridable"].as_bool(), Option::Some(true));
+
+    assert!(archive.other_files.contains_key("package.json"));
+}
+
+/**
+* Test that writing an archive back out reproduces every file of the export
+*
+*/
+#[cfg(not(feature = "strict"))]
+#[test]
+fn test_agent_archive_round_trip() {
+
+    let archive = AgentArchive::read(Cursor::new(AGENT_ZIP)).unwrap();
+
+    assert_eq!(zip_contents(AGENT_ZIP), zip_contents(&rewrite(&archive)));
+}
+
+/**
+* Test that the strict feature rejects the fields of an export the crate doesn't model
+*
+*/
+#[cfg(feature = "strict")]
+#[test]
+fn test_read_agent_archive_strict() {
+
+    match AgentArchive::read(Cursor::new(AGENT_ZIP)) {
+        Result::Err(ArchiveError::Json{ .. }) => (),
+        _ => panic!("expected a Json error")
+    }
+}
+
+/**
+* Test that edits to an archive are written out and read back
+*
+*/
+#[cfg(not(feature = "strict"))]
+#[test]
+fn test_edit_agent_archive() {
+    use archive::ArchivedIntent;
+    use client::{EntityEntry, Intent};
+
+    let mut archive = AgentArchive::read(Cursor::new(AGENT_ZIP)).unwrap();
+
+    archive.agent.description = String::from("Weather, small talk and trains");
+    archive.entity_mut("city").unwrap().entries.get_mut("en").unwrap().push(EntityEntry{
+        value: String::from("Berlin"),
+        synonyms: vec![String::from("Berlin")],
+        ..Default::default()
+    });
+
+    let mut trains : Intent = serde_json::from_str(include_str!("fixtures/intent.json")).unwrap();
+    trains.id = Option::None;
+    trains.name = String::from("trains/departures");
+    let phrases = trains.user_says.split_off(0);
+    let mut user_says = BTreeMap::new();
+    user_says.insert(String::from("en"), phrases);
+    archive.intents.push(ArchivedIntent{ intent: trains, user_says: user_says, file_stem: Option::None });
+
+    let rewritten = rewrite(&archive);
+
+    let contents = zip_contents(&rewritten);
+    assert!(contents.contains_key("intents/trains_departures.json"));
+    assert!(contents.contains_key("intents/trains_departures_usersays_en.json"));
+    assert_eq!(contents["agent.json"]["webhook"]["headers"]["X-Token"].as_str(), Option::Some("abc"));
+
+    let reread = AgentArchive::read(Cursor::new(rewritten)).unwrap();
+    assert_eq!(reread.agent.description, "Weather, small talk and trains");
+    assert_eq!(reread.entity("city").unwrap().entries["en"].len(), 3);
+    assert_eq!(reread.intent("trains/departures").unwrap().user_says["en"].len(), 2);
+}
+
+/**
+* Test that files keep their names through a read and write, and that a name holding the
+* phrases marker isn't taken for a phrases file
+*
+*/
+#[test]
+fn test_archive_file_names_kept() {
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    zip.start_file("agent.json", FileOptions::default()).unwrap();
+    zip.write_all(br#"{"language":"en"}"#).unwrap();
+    zip.start_file("intents/weather_ forecast.json", FileOptions::default()).unwrap();
+    zip.write_all(br#"{"name":"weather: forecast"}"#).unwrap();
+    zip.start_file("intents/faq_usersays_answers.json", FileOptions::default()).unwrap();
+    zip.write_all(br#"{"name":"faq_usersays_answers"}"#).unwrap();
+    zip.start_file("intents/faq_usersays_answers_usersays_pt-br.json", FileOptions::default()).unwrap();
+    zip.write_all(b"[]").unwrap();
+    let exported = zip.finish().unwrap().into_inner();
+
+    let archive = AgentArchive::read(Cursor::new(exported.clone())).unwrap();
+    assert_eq!(archive.intents.len(), 2);
+    assert!(archive.intent("weather: forecast").is_some());
+    assert!(archive.intent("faq_usersays_answers").unwrap().user_says.contains_key("pt-br"));
+
+    let names: Vec<String> = zip_contents(&rewrite(&archive)).keys().cloned().collect();
+    let exported_names: Vec<String> = zip_contents(&exported).keys().cloned().collect();
+    assert_eq!(names, exported_names);
+}
+
+/**
+* Test that phrases without their intent are reported rather than dropped
+*
+*/
+#[test]
+fn test_archive_missing_files() {
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    zip.start_file("intents/orphan_usersays_en.json", FileOptions::default()).unwrap();
+    zip.write_all(b"[]").unwrap();
+    let without_agent = zip.finish().unwrap().into_inner();
+
+    match AgentArchive::read(Cursor::new(without_agent)) {
+        Result::Err(ArchiveError::MissingFile(file)) => assert_eq!(file, "agent.json"),
+        _ => panic!("expected MissingFile")
+    }
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    zip.start_file("agent.json", FileOptions::default()).unwrap();
+    zip.write_all(br#"{"language":"en"}"#).unwrap();
+    zip.start_file("intents/orphan_usersays_en.json", FileOptions::default()).unwrap();
+    zip.write_all(b"[]").unwrap();
+    let orphaned = zip.finish().unwrap().into_inner();
+
+    match AgentArchive::read(Cursor::new(orphaned)) {
+        Result::Err(ArchiveError::MissingFile(file)) => assert_eq!(file, "intents/orphan.json"),
+        _ => panic!("expected MissingFile")
+    }
+}
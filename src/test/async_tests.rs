@@ -0,0 +1,94 @@
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::Future;
+use tokio_core::reactor::Core;
+
+use async_client::{AsyncApiAIClient, BlockingTransport};
+use client::{ApiRequest, ApiError};
+use transport::{MemoryTransport, Timeouts};
+use test::mock_server::{MockServer, json_response};
+use test::fixtures::GREETING_RESPONSE;
+
+/**
+* Test that the async client resolves a query sent through an adapted blocking transport
+*
+*/
+#[test]
+fn test_async_query_with_blocking_transport() {
+
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(200, GREETING_RESPONSE);
+
+    let client = AsyncApiAIClient{
+        version: String::from("20150910"),
+        access_token: String::from("ce2f54f8eb444d74af85f89e30ef2fd3"),
+        base_url: String::from("https://api.api.ai/v1"),
+        transport: Rc::new(BlockingTransport(transport.clone())),
+        timeouts: Timeouts::default()
+    };
+
+    let req = ApiRequest{
+        query: Option::Some(String::from("Hello!")),
+        ..Default::default()
+    };
+
+    let response = client.query(req).wait().unwrap();
+    assert_eq!(response.result.action, "smalltalk.greetings");
+    assert_eq!(transport.requests()[0].url, "https://api.api.ai/v1/query?v=20150910");
+}
+
+/**
+* Test that the hyper based async transport talks HTTP to a real socket on the event loop
+*
+*/
+#[test]
+fn test_async_query_over_http() {
+
+    let server = MockServer::start(vec![json_response(200, GREETING_RESPONSE)]);
+
+    let mut core = Core::new().unwrap();
+
+    let mut client = AsyncApiAIClient::new(&core.handle(), String::from("ce2f54f8eb444d74af85f89e30ef2fd3")).unwrap();
+    client.base_url = server.url.clone();
+
+    let req = ApiRequest{
+        query: Option::Some(String::from("Hello!")),
+        ..Default::default()
+    };
+
+    let response = core.run(client.query(req)).unwrap();
+    assert_eq!(response.result.fulfillment.speech, "Hi there!");
+
+    let requests = server.requests();
+    assert!(requests[0].starts_with("POST /query?v=20150910 HTTP/1.1"));
+    assert!(requests[0].contains("Bearer ce2f54f8eb444d74af85f89e30ef2fd3"));
+    assert!(requests[0].contains(r#""query":"Hello!""#));
+}
+
+/**
+* Test that the overall deadline is enforced by a timer on the event loop
+*
+*/
+#[test]
+fn test_async_query_deadline() {
+
+    let server = MockServer::start_delayed(vec![json_response(200, GREETING_RESPONSE)], Duration::from_millis(500));
+
+    let mut core = Core::new().unwrap();
+
+    let mut client = AsyncApiAIClient::new(&core.handle(), String::from("ce2f54f8eb444d74af85f89e30ef2fd3")).unwrap();
+    client.base_url = server.url.clone();
+    client.timeouts.request = Option::Some(Duration::from_millis(100));
+
+    let req = ApiRequest{
+        query: Option::Some(String::from("Hello!")),
+        ..Default::default()
+    };
+
+    match core.run(client.query(req)) {
+        Result::Err(ApiError::Timeout(_)) => (),
+        _ => panic!("Expected a Timeout error")
+    }
+}
@@ -0,0 +1,141 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use builder::ConfigProblem;
+use client::{ApiAIClient,ApiRequest};
+use retry::RetryPolicy;
+use transport::{MemoryTransport, Timeouts};
+use test::fixtures::{GREETING_RESPONSE, TOKEN};
+
+/**
+* Test that a valid configuration builds a client with defaults filled in
+*
+*/
+#[test]
+fn test_build_with_defaults() {
+
+    let client = ApiAIClient::builder()
+        .access_token(TOKEN)
+        .build()
+        .unwrap();
+
+    assert_eq!(client.access_token, TOKEN);
+    assert_eq!(client.base_url, "https://api.api.ai/v1");
+    assert_eq!(client.version, "20150910");
+    assert_eq!(client.timeouts, Timeouts::default());
+}
+
+/**
+* Test that trailing slashes are stripped from the base url
+*
+*/
+#[test]
+fn test_base_url_normalized() {
+
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(200, GREETING_RESPONSE);
+
+    let client = ApiAIClient::builder()
+        .access_token(TOKEN)
+        .base_url("https://example.com/apiai/v1//")
+        .transport(transport.clone())
+        .build()
+        .unwrap();
+
+    assert_eq!(client.base_url, "https://example.com/apiai/v1");
+
+    client.query(ApiRequest{
+        query: Option::Some(String::from("Hello!")),
+        ..Default::default()
+    }).unwrap();
+
+    assert_eq!(transport.requests()[0].url, "https://example.com/apiai/v1/query?v=20150910");
+
+    let client = ApiAIClient::builder()
+        .access_token(TOKEN)
+        .base_url("http://localhost:8080/")
+        .build()
+        .unwrap();
+
+    assert_eq!(client.base_url, "http://localhost:8080");
+}
+
+/**
+* Test that the transport, retry and timeout settings are carried over
+*
+*/
+#[test]
+fn test_settings_carried_over() {
+
+    let timeouts = Timeouts{
+        request: Option::Some(Duration::from_secs(5)),
+        ..Default::default()
+    };
+
+    let client = ApiAIClient::builder()
+        .access_token(TOKEN)
+        .version("20170712")
+        .retry_policy(RetryPolicy::exponential(3))
+        .timeouts(timeouts.clone())
+        .build()
+        .unwrap();
+
+    assert_eq!(client.version, "20170712");
+    assert_eq!(client.retry_policy.max_attempts, 3);
+    assert_eq!(client.timeouts, timeouts);
+}
+
+/**
+* Test that every problem is reported at once
+*
+*/
+#[test]
+fn test_all_problems_reported() {
+
+    let err = ApiAIClient::builder()
+        .access_token("not-a-token")
+        .base_url("ftp://example.com")
+        .version("2015-09-10")
+        .retry_policy(RetryPolicy::exponential(0))
+        .timeouts(Timeouts{ connect: Option::Some(Duration::from_secs(0)), ..Default::default() })
+        .build()
+        .err()
+        .unwrap();
+
+    assert_eq!(err.problems, vec![
+        ConfigProblem::InvalidAccessToken,
+        ConfigProblem::InvalidBaseUrl{ url: String::from("ftp://example.com"), reason: String::from("unsupported scheme 'ftp'") },
+        ConfigProblem::InvalidVersion(String::from("2015-09-10")),
+        ConfigProblem::ZeroTimeout("connect"),
+        ConfigProblem::NoAttempts,
+    ]);
+
+    let message = err.to_string();
+    assert!(message.contains("access token"));
+    assert!(!message.contains("not-a-token"));
+}
+
+/**
+* Test the individual checks on the token, url and version
+*
+*/
+#[test]
+fn test_individual_checks() {
+
+    let problems = |builder: ::builder::ApiAIClientBuilder| builder.build().err().unwrap().problems;
+
+    assert_eq!(problems(ApiAIClient::builder()), vec![ConfigProblem::MissingAccessToken]);
+
+    assert_eq!(problems(ApiAIClient::builder().access_token(TOKEN).version("20151340")),
+               vec![ConfigProblem::InvalidVersion(String::from("20151340"))]);
+
+    match problems(ApiAIClient::builder().access_token(TOKEN).base_url("not a url"))[0] {
+        ConfigProblem::InvalidBaseUrl{ .. } => (),
+        ref other => panic!("Expected InvalidBaseUrl, got {:?}", other)
+    }
+
+    match problems(ApiAIClient::builder().access_token(TOKEN).base_url("https://api.api.ai/v1?lang=en"))[0] {
+        ConfigProblem::InvalidBaseUrl{ .. } => (),
+        ref other => panic!("Expected InvalidBaseUrl, got {:?}", other)
+    }
+}
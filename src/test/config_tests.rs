@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs::{self, File};
+use std::io::Write;
+
+use builder::ConfigProblem;
+use client::{ApiAIClient, DEFAULT_BASE_URL, DEFAULT_VERSION};
+use config::{ConfigError, ConfigFile, ConfigFormat, profile_from_vars};
+
+static TOML_CONFIG: &'static str = r#"
+version = "20170712"
+default_profile = "support"
+
+[profiles.support]
+access_token = "ce2f54f8eb444d74af85f89e30ef2fd3"
+
+[profiles.sales]
+access_token = "0f5e2ea1a1b04bd9a4b7f3b0c5a94b46"
+base_url = "https://example.com/v1/"
+"#;
+
+static JSON_CONFIG: &'static str = r#"{
+    "profiles": {
+        "support": { "access_token": "ce2f54f8eb444d74af85f89e30ef2fd3" }
+    }
+}"#;
+
+/**
+* Test that profiles fall back to the top level settings and then the defaults
+*
+*/
+#[test]
+fn test_toml_profiles() {
+
+    let config = ConfigFile::parse(TOML_CONFIG, ConfigFormat::Toml).unwrap();
+
+    let support = config.profile(Option::None).unwrap();
+    assert_eq!(support.access_token.as_ref().unwrap(), "ce2f54f8eb444d74af85f89e30ef2fd3");
+    assert_eq!(support.version.as_ref().unwrap(), "20170712");
+
+    let client = config.profile(Option::Some("sales")).unwrap().builder().build().unwrap();
+    assert_eq!(client.access_token, "0f5e2ea1a1b04bd9a4b7f3b0c5a94b46");
+    assert_eq!(client.base_url, "https://example.com/v1");
+    assert_eq!(client.version, "20170712");
+
+    match config.profile(Option::Some("billing")) {
+        Result::Err(ConfigError::UnknownProfile(name)) => assert_eq!(name, "billing"),
+        _ => panic!("Expected an UnknownProfile error")
+    }
+}
+
+/**
+* Test that a JSON file with a single profile uses it and the library defaults
+*
+*/
+#[test]
+fn test_json_single_profile() {
+
+    let config = ConfigFile::parse(JSON_CONFIG, ConfigFormat::Json).unwrap();
+    let client = config.profile(Option::None).unwrap().builder().build().unwrap();
+
+    assert_eq!(client.access_token, "ce2f54f8eb444d74af85f89e30ef2fd3");
+    assert_eq!(client.base_url, DEFAULT_BASE_URL);
+    assert_eq!(client.version, DEFAULT_VERSION);
+}
+
+/**
+* Test loading a client from a file on disk, detecting the format from the extension
+*
+*/
+#[test]
+fn test_from_config_file() {
+
+    let dir = env::temp_dir().join(format!("apiai-config-test-{}", ::std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let toml_path = dir.join("agents.toml");
+    File::create(&toml_path).unwrap().write_all(TOML_CONFIG.as_bytes()).unwrap();
+    let json_path = dir.join("agents.json");
+    File::create(&json_path).unwrap().write_all(JSON_CONFIG.as_bytes()).unwrap();
+
+    let client = ApiAIClient::from_config_file(&toml_path).unwrap();
+    assert_eq!(client.access_token, "ce2f54f8eb444d74af85f89e30ef2fd3");
+
+    let client = ApiAIClient::from_config_profile(&toml_path, "sales").unwrap();
+    assert_eq!(client.access_token, "0f5e2ea1a1b04bd9a4b7f3b0c5a94b46");
+
+    let client = ApiAIClient::from_config_file(&json_path).unwrap();
+    assert_eq!(client.version, DEFAULT_VERSION);
+
+    match ApiAIClient::from_config_file(dir.join("missing.toml")) {
+        Result::Err(ConfigError::Io(_)) => (),
+        _ => panic!("Expected an Io error")
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+/**
+* Test reading settings from environment variables, ignoring empty values
+*
+*/
+#[test]
+fn test_profile_from_vars() {
+
+    let mut vars = HashMap::new();
+    vars.insert("APIAI_ACCESS_TOKEN", "ce2f54f8eb444d74af85f89e30ef2fd3");
+    vars.insert("APIAI_BASE_URL", "");
+    vars.insert("APIAI_VERSION", "20170712");
+
+    let profile = profile_from_vars(|name| vars.get(name).map(|value| String::from(*value)));
+    let client = profile.builder().build().unwrap();
+
+    assert_eq!(client.access_token, "ce2f54f8eb444d74af85f89e30ef2fd3");
+    assert_eq!(client.base_url, DEFAULT_BASE_URL);
+    assert_eq!(client.version, "20170712");
+
+    let err = profile_from_vars(|_| Option::None).builder().build().err().unwrap();
+    assert_eq!(err.problems, vec![ConfigProblem::MissingAccessToken]);
+}
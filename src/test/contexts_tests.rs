@@ -0,0 +1,87 @@
+use client::{ApiContext, ApiError, Parameters};
+use transport::HttpMethod;
+use test::fixtures::{TOKEN, client_with_transport};
+
+use serde_json;
+
+/**
+* Test listing and fetching the contexts of a session
+*
+*/
+#[test]
+fn test_get_contexts() {
+
+    let (client, transport) = client_with_transport(TOKEN);
+    transport.push_json(200, r#"[{"name":"weather","parameters":{"city":"London"},"lifespan":4},{"name":"greeted","lifespan":1}]"#);
+    transport.push_json(200, r#"{"name":"weather","parameters":{"city":"London"},"lifespan":4}"#);
+
+    let contexts = client.contexts("session 1").unwrap();
+    assert_eq!(contexts.len(), 2);
+    assert_eq!(contexts[0].parameters.get_str("city"), Option::Some("London"));
+    assert!(contexts[1].parameters.is_empty());
+    assert_eq!(contexts[1].lifespan, Option::Some(1));
+
+    let context = client.context("session 1", "weather").unwrap();
+    assert_eq!(context.name, "weather");
+
+    let requests = transport.requests();
+    assert_eq!(requests[0].method, HttpMethod::Get);
+    assert_eq!(requests[0].url, "https://api.api.ai/v1/contexts?sessionId=session+1&v=20150910");
+    assert!(requests[0].headers.contains(&(String::from("Authorization"), format!("Bearer {}", TOKEN))));
+    assert_eq!(requests[1].url, "https://api.api.ai/v1/contexts/weather?sessionId=session+1&v=20150910");
+}
+
+/**
+* Test adding contexts to a session
+*
+*/
+#[test]
+fn test_add_contexts() {
+
+    let (client, transport) = client_with_transport(TOKEN);
+    transport.push_json(200, r#"{"names":["weather"],"status":{"code":200,"errorType":"success"}}"#);
+
+    let mut parameters = Parameters::new();
+    parameters.insert("city", "Paris");
+
+    let names = client.add_contexts("12345", &[ApiContext{
+        name: String::from("weather"),
+        parameters: parameters,
+        lifespan: Option::Some(2),
+        ..Default::default()
+    }]).unwrap();
+    assert_eq!(names, vec![String::from("weather")]);
+
+    let sent = &transport.requests()[0];
+    assert_eq!(sent.method, HttpMethod::Post);
+    assert_eq!(sent.url, "https://api.api.ai/v1/contexts?sessionId=12345&v=20150910");
+    assert!(sent.headers.contains(&(String::from("Content-Type"), String::from("application/json"))));
+
+    let body: serde_json::Value = serde_json::from_slice(&sent.body).unwrap();
+    assert_eq!(body, serde_json::from_str::<serde_json::Value>(r#"[{"name":"weather","parameters":{"city":"Paris"},"lifespan":2}]"#).unwrap());
+}
+
+/**
+* Test deleting contexts, including the mapping of a missing context onto NotFound
+*
+*/
+#[test]
+fn test_delete_contexts() {
+
+    let (client, transport) = client_with_transport(TOKEN);
+    transport.push_json(200, r#"{"status":{"code":200,"errorType":"success"}}"#);
+    transport.push_json(404, r#"{"status":{"code":404,"errorType":"not_found","errorDetails":"Context not found"}}"#);
+
+    client.delete_contexts("12345").unwrap();
+
+    match client.delete_context("12345", "order/pending") {
+        Result::Err(ApiError::NotFound(details)) => assert_eq!(details.error_details.unwrap(), "Context not found"),
+        _ => panic!("expected NotFound")
+    }
+
+    let requests = transport.requests();
+    assert_eq!(requests[0].method, HttpMethod::Delete);
+    assert_eq!(requests[0].url, "https://api.api.ai/v1/contexts?sessionId=12345&v=20150910");
+    assert!(requests[0].body.is_empty());
+    assert_eq!(requests[1].url, "https://api.api.ai/v1/contexts/order%2Fpending?sessionId=12345&v=20150910");
+}
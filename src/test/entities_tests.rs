@@ -0,0 +1,122 @@
+use client::{ApiError, Entity, EntityEntry};
+use transport::HttpMethod;
+use test::fixtures::{DEVELOPER_TOKEN, SUCCESS, client_with_transport};
+
+use serde_json;
+
+fn entry(value: &str, synonyms: &[&str]) -> EntityEntry {
+    EntityEntry{
+        value: String::from(value),
+        synonyms: synonyms.iter().map(|synonym| String::from(*synonym)).collect(),
+        ..Default::default()
+    }
+}
+
+fn json(body: &[u8]) -> serde_json::Value {
+    serde_json::from_slice(body).unwrap()
+}
+
+/**
+* Test listing entities and fetching one with its entries
+*
+*/
+#[test]
+fn test_get_entities() {
+
+    let (client, transport) = client_with_transport(DEVELOPER_TOKEN);
+    transport.push_json(200, r#"[{"id":"57a9b4e5-c2d6-4dd4-a1bf-0fbd51a4b8e7","name":"store","count":2,"preview":"london <= (london, ldn), paris <= (paris)"}]"#);
+    transport.push_json(200, r#"{"id":"57a9b4e5-c2d6-4dd4-a1bf-0fbd51a4b8e7","name":"store","entries":[{"value":"london","synonyms":["london","ldn"]},{"value":"paris","synonyms":["paris"]}],"isEnum":false,"automatedExpansion":true}"#);
+
+    let entities = client.entities().unwrap();
+    assert_eq!(entities.len(), 1);
+    assert_eq!(entities[0].name, "store");
+    assert_eq!(entities[0].count, 2);
+
+    let entity = client.entity("store").unwrap();
+    assert_eq!(entity.id.unwrap(), "57a9b4e5-c2d6-4dd4-a1bf-0fbd51a4b8e7");
+    assert_eq!(entity.entries.len(), 2);
+    assert_eq!(entity.entries[0].synonyms, vec![String::from("london"), String::from("ldn")]);
+    assert!(entity.automated_expansion);
+    assert!(!entity.is_enum);
+
+    let requests = transport.requests();
+    assert_eq!(requests[0].method, HttpMethod::Get);
+    assert_eq!(requests[0].url, "https://api.api.ai/v1/entities?v=20150910");
+    assert!(requests[0].headers.contains(&(String::from("Authorization"), format!("Bearer {}", DEVELOPER_TOKEN))));
+    assert_eq!(requests[1].url, "https://api.api.ai/v1/entities/store?v=20150910");
+}
+
+/**
+* Test creating, updating and deleting an entity
+*
+*/
+#[test]
+fn test_entity_lifecycle() {
+
+    let (client, transport) = client_with_transport(DEVELOPER_TOKEN);
+    transport.push_json(200, r#"{"id":"d1c3a8b2-0f6e-4c55-9a4b-2c1e5f7d9b30","status":{"code":200,"errorType":"success"}}"#);
+    transport.push_json(200, SUCCESS);
+    transport.push_json(200, SUCCESS);
+
+    let mut entity = Entity{
+        id: Option::None,
+        name: String::from("product"),
+        entries: vec![entry("t-shirt", &["t-shirt", "tee"])],
+        is_enum: false,
+        automated_expansion: false,
+        ..Default::default()
+    };
+
+    let id = client.create_entity(&entity).unwrap();
+    assert_eq!(id, "d1c3a8b2-0f6e-4c55-9a4b-2c1e5f7d9b30");
+
+    entity.id = Option::Some(id.clone());
+    entity.entries.push(entry("hoodie", &["hoodie", "hoody"]));
+    client.update_entity(&id, &entity).unwrap();
+
+    client.delete_entity(&id).unwrap();
+
+    let requests = transport.requests();
+    assert_eq!(requests[0].method, HttpMethod::Post);
+    assert_eq!(requests[0].url, "https://api.api.ai/v1/entities?v=20150910");
+    assert_eq!(json(&requests[0].body), json(br#"{"name":"product","entries":[{"value":"t-shirt","synonyms":["t-shirt","tee"]}],"isEnum":false,"automatedExpansion":false}"#));
+
+    assert_eq!(requests[1].method, HttpMethod::Put);
+    assert_eq!(requests[1].url, "https://api.api.ai/v1/entities/d1c3a8b2-0f6e-4c55-9a4b-2c1e5f7d9b30?v=20150910");
+    assert_eq!(json(&requests[1].body)["id"], json(br#""d1c3a8b2-0f6e-4c55-9a4b-2c1e5f7d9b30""#));
+    assert_eq!(json(&requests[1].body)["entries"].as_array().unwrap().len(), 2);
+
+    assert_eq!(requests[2].method, HttpMethod::Delete);
+    assert!(requests[2].body.is_empty());
+}
+
+/**
+* Test adding, updating and deleting the entries of an entity
+*
+*/
+#[test]
+fn test_entity_entries() {
+
+    let (client, transport) = client_with_transport(DEVELOPER_TOKEN);
+    transport.push_json(200, SUCCESS);
+    transport.push_json(200, SUCCESS);
+    transport.push_json(200, SUCCESS);
+    transport.push_json(400, r#"{"status":{"code":400,"errorType":"bad_request","errorDetails":"Entity 'colour' does not exist"}}"#);
+
+    client.add_entity_entries("store", &[entry("berlin", &["berlin"])]).unwrap();
+    client.update_entity_entries("store", &[entry("london", &["london", "ldn", "the big smoke"])]).unwrap();
+    client.delete_entity_entries("store", &[String::from("paris")]).unwrap();
+
+    match client.add_entity_entries("colour", &[entry("red", &["red"])]) {
+        Result::Err(ApiError::BadRequest(details)) => assert_eq!(details.error_type, "bad_request"),
+        _ => panic!("expected BadRequest")
+    }
+
+    let requests = transport.requests();
+    let methods: Vec<HttpMethod> = requests.iter().map(|request| request.method.clone()).collect();
+    assert_eq!(methods, vec![HttpMethod::Post, HttpMethod::Put, HttpMethod::Delete, HttpMethod::Post]);
+    assert_eq!(requests[0].url, "https://api.api.ai/v1/entities/store/entries?v=20150910");
+    assert_eq!(json(&requests[0].body), json(br#"[{"value":"berlin","synonyms":["berlin"]}]"#));
+    assert_eq!(json(&requests[2].body), json(br#"["paris"]"#));
+    assert!(requests[2].headers.contains(&(String::from("Content-Type"), String::from("application/json"))));
+}
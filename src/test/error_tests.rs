@@ -0,0 +1,209 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{self, UTC};
+
+use serde::ser::{Serialize, Serializer, Error};
+
+use client::{ApiAIClient,ApiError};
+use tls::{Certificate, TlsConfig};
+use transport::{HttpResponse, HyperTransport, MemoryTransport, PoolConfig, Timeouts};
+use test::fixtures::hello;
+
+fn client_with_response(response: HttpResponse) -> ApiAIClient {
+    let transport = MemoryTransport::new();
+    transport.push_response(response);
+
+    ApiAIClient{
+        transport: Arc::new(transport),
+        ..Default::default()
+    }
+}
+
+/**
+* Test that a 401 with a status body is mapped onto Unauthorized with the status details
+*
+*/
+#[test]
+fn test_unauthorized_status() {
+
+    let body = r#"{"status":{"code":401,"errorType":"unauthorized","errorDetails":"Authentication parameters missing"}}"#;
+
+    let client = client_with_response(HttpResponse{
+        status: 401,
+        headers: Vec::new(),
+        body: body.as_bytes().to_vec()
+    });
+
+    match client.query(hello()) {
+        Result::Err(ApiError::Unauthorized(details)) => {
+            assert_eq!(details.code, 401);
+            assert_eq!(details.error_type, "unauthorized");
+            assert_eq!(details.error_details.unwrap(), "Authentication parameters missing");
+            assert_eq!(details.body, body);
+        },
+        _ => panic!("Expected an Unauthorized error")
+    }
+}
+
+/**
+* Test that an error status in the body is honoured even when the HTTP status is 200
+*
+*/
+#[test]
+fn test_error_status_in_body() {
+
+    let body = r#"{"status":{"code":400,"errorType":"bad_request","errorDetails":"Query text is empty"}}"#;
+
+    let client = client_with_response(HttpResponse{
+        status: 200,
+        headers: Vec::new(),
+        body: body.as_bytes().to_vec()
+    });
+
+    match client.query(hello()) {
+        Result::Err(ApiError::BadRequest(details)) => assert_eq!(details.error_type, "bad_request"),
+        _ => panic!("Expected a BadRequest error")
+    }
+}
+
+/**
+* Test that a server error without a JSON body still maps onto ServerError
+*
+*/
+#[test]
+fn test_server_error_without_status_body() {
+
+    let client = client_with_response(HttpResponse{
+        status: 503,
+        headers: Vec::new(),
+        body: b"Service Unavailable".to_vec()
+    });
+
+    let err = client.query(hello()).err().unwrap();
+
+    match err {
+        ApiError::ServerError(ref details) => {
+            assert_eq!(details.code, 503);
+            assert_eq!(details.error_type, "");
+            assert_eq!(details.body, "Service Unavailable");
+        },
+        _ => panic!("Expected a ServerError")
+    }
+
+    assert_eq!(format!("{}", err), "API.ai returned status 503 ()");
+}
+
+/**
+* Test that Retry-After is read when given as an HTTP-date as well as in seconds
+*
+*/
+#[test]
+fn test_retry_after_http_date() {
+
+    let at = UTC::now() + chrono::Duration::seconds(30);
+
+    let client = client_with_response(HttpResponse{
+        status: 429,
+        headers: vec![(String::from("Retry-After"), at.format("%a, %d %b %Y %H:%M:%S GMT").to_string())],
+        body: Vec::new()
+    });
+
+    match client.query(hello()) {
+        Result::Err(ApiError::RateLimited(details)) => {
+            let retry_after = details.retry_after.unwrap();
+            assert!(retry_after > Duration::from_secs(25) && retry_after <= Duration::from_secs(30));
+        },
+        _ => panic!("Expected a RateLimited error")
+    }
+
+    let client = client_with_response(HttpResponse{
+        status: 429,
+        headers: vec![(String::from("Retry-After"), String::from("Wed, 21 Oct 2015 07:28:00 GMT"))],
+        body: Vec::new()
+    });
+
+    match client.query(hello()) {
+        Result::Err(ApiError::RateLimited(details)) => assert_eq!(details.retry_after, Option::Some(Duration::from_secs(0))),
+        _ => panic!("Expected a RateLimited error")
+    }
+}
+
+/**
+* Test that ApiError can be used as a std::error::Error
+*
+*/
+#[test]
+fn test_api_error_is_std_error() {
+    use std::error::Error;
+
+    let client = client_with_response(HttpResponse{
+        status: 404,
+        headers: Vec::new(),
+        body: br#"{"status":{"code":404,"errorType":"not_found"}}"#.to_vec()
+    });
+
+    let err : Box<Error> = Box::new(client.query(hello()).err().unwrap());
+
+    assert_eq!(err.to_string(), "API.ai returned status 404 (not_found)");
+}
+
+/**
+* A request type whose serializer always fails
+*/
+struct BrokenRequest;
+
+impl Serialize for BrokenRequest {
+    fn serialize<S>(&self, _serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        Result::Err(S::Error::custom("broken serializer"))
+    }
+}
+
+/**
+* Test that a query that fails to serialize is returned as RequestSerialization, without a panic
+* and without anything being sent
+*/
+#[test]
+fn test_request_serialization_failure() {
+    use std::error::Error;
+
+    let transport = Arc::new(MemoryTransport::new());
+    let client = ApiAIClient{
+        transport: transport.clone(),
+        ..Default::default()
+    };
+
+    let outcome = client.execute(&BrokenRequest, Timeouts::default());
+
+    match outcome.result {
+        Result::Err(err @ ApiError::RequestSerialization(_)) => assert!(err.cause().is_some()),
+        _ => panic!("Expected a RequestSerialization error")
+    }
+    assert_eq!(outcome.attempts, 0);
+    assert!(transport.requests().is_empty());
+}
+
+/**
+* Test that a TLS initialisation failure is handed back to the caller with its source
+*
+*/
+#[test]
+fn test_tls_init_failure() {
+    use std::error::Error;
+
+    let tls = TlsConfig{
+        root_certificates: vec![Certificate::Der(b"not a certificate".to_vec())],
+        ..Default::default()
+    };
+
+    let client = ApiAIClient{
+        transport: Arc::new(HyperTransport::with_settings(PoolConfig::default(), Option::None, tls)),
+        ..Default::default()
+    };
+
+    match client.query(hello()) {
+        Result::Err(err @ ApiError::TlsInit(_)) => assert!(err.cause().is_some()),
+        _ => panic!("Expected a TlsInit error")
+    }
+}
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use client::{ApiAIClient, ApiRequest};
+use transport::MemoryTransport;
+
+/**
+* A client access token, as used for queries
+*/
+pub static TOKEN: &'static str = "ce2f54f8eb444d74af85f89e30ef2fd3";
+
+/**
+* A developer access token, as needed to manage intents and entities
+*/
+pub static DEVELOPER_TOKEN: &'static str = "6b1d7ae6d5e34bd8a9e2a4d6a3c7f0e1";
+
+/**
+* api.ai's reply to `hello()`
+*/
+pub static GREETING_RESPONSE: &'static str = r#"{"id":"b340a1f7-abee-4e13-9bdd-5e8938a48b7d","timestamp":"2017-02-09T15:38:26.548Z","lang":"en","result":{"source":"domains","resolvedQuery":"Hello!","action":"smalltalk.greetings","actionIncomplete":false,"parameters":{},"contexts":[],"metadata":{},"fulfillment":{"speech":"Hi there!"},"score":1},"status":{"code":200,"errorType":"success"},"sessionId":"12345"}"#;
+
+/**
+* The body of a successful call to one of the management endpoints
+*/
+pub static SUCCESS: &'static str = r#"{"status":{"code":200,"errorType":"success"}}"#;
+
+pub fn hello() -> ApiRequest {
+    ApiRequest{
+        query: Option::Some(String::from("Hello!")),
+        ..Default::default()
+    }
+}
+
+/**
+* A client that sends its requests to a MemoryTransport, returned alongside it
+*/
+pub fn client_with_transport(access_token: &str) -> (ApiAIClient, Arc<MemoryTransport>) {
+    let transport = Arc::new(MemoryTransport::new());
+
+    let client = ApiAIClient{
+        access_token: String::from(access_token),
+        transport: transport.clone(),
+        ..Default::default()
+    };
+
+    (client, transport)
+}
@@ -0,0 +1,77 @@
+use client::{ApiError, Intent};
+use transport::HttpMethod;
+use test::fixtures::{DEVELOPER_TOKEN, SUCCESS, client_with_transport};
+
+use serde_json;
+
+static INTENT: &'static str = include_str!("fixtures/intent.json");
+
+/**
+* Test listing intents and fetching one in full
+*
+*/
+#[test]
+fn test_get_intents() {
+
+    let (client, transport) = client_with_transport(DEVELOPER_TOKEN);
+    transport.push_json(200, r#"[{"id":"2a7c1d04-3b5e-4f8a-9d61-7e0b8c2f4a15","name":"weather.forecast","contextIn":["weather"],"events":[{"name":"WEATHER"}],"parameters":[],"contextOut":[],"actions":["weather.forecast"],"priority":500000,"fallbackIntent":false}]"#);
+    transport.push_json(200, INTENT);
+
+    let intents = client.intents().unwrap();
+    assert_eq!(intents.len(), 1);
+    assert_eq!(intents[0].context_in, vec![String::from("weather")]);
+    assert_eq!(intents[0].actions, vec![String::from("weather.forecast")]);
+    assert_eq!(intents[0].events[0].name, "WEATHER");
+
+    let intent = client.intent(&intents[0].id).unwrap();
+    assert_eq!(intent.user_says.len(), 2);
+    assert_eq!(intent.responses[0].affected_contexts[0].lifespan, Option::Some(5));
+
+    let requests = transport.requests();
+    assert_eq!(requests[0].method, HttpMethod::Get);
+    assert_eq!(requests[0].url, "https://api.api.ai/v1/intents?v=20150910");
+    assert!(requests[0].headers.contains(&(String::from("Authorization"), format!("Bearer {}", DEVELOPER_TOKEN))));
+    assert_eq!(requests[1].url, "https://api.api.ai/v1/intents/2a7c1d04-3b5e-4f8a-9d61-7e0b8c2f4a15?v=20150910");
+}
+
+/**
+* Test pushing an intent kept in a file: create it, update it and delete it again
+*
+*/
+#[test]
+fn test_intent_lifecycle() {
+
+    let (client, transport) = client_with_transport(DEVELOPER_TOKEN);
+    transport.push_json(200, r#"{"id":"9b0e5c3a-1d7f-4b28-a6e9-3f5c8d1b7e24","status":{"code":200,"errorType":"success"}}"#);
+    transport.push_json(200, SUCCESS);
+    transport.push_json(200, SUCCESS);
+    transport.push_json(404, r#"{"status":{"code":404,"errorType":"not_found","errorDetails":"Intent not found"}}"#);
+
+    let mut intent : Intent = serde_json::from_str(INTENT).unwrap();
+    intent.id = Option::None;
+
+    let id = client.create_intent(&intent).unwrap();
+    assert_eq!(id, "9b0e5c3a-1d7f-4b28-a6e9-3f5c8d1b7e24");
+
+    intent.id = Option::Some(id.clone());
+    intent.priority = Option::Some(750000);
+    client.update_intent(&id, &intent).unwrap();
+    client.delete_intent(&id).unwrap();
+
+    match client.delete_intent(&id) {
+        Result::Err(ApiError::NotFound(_)) => (),
+        _ => panic!("expected NotFound")
+    }
+
+    let requests = transport.requests();
+    let methods: Vec<HttpMethod> = requests.iter().map(|request| request.method.clone()).collect();
+    assert_eq!(methods, vec![HttpMethod::Post, HttpMethod::Put, HttpMethod::Delete, HttpMethod::Delete]);
+
+    let created : serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert!(created.get("id").is_none());
+    assert_eq!(created["userSays"].as_array().unwrap().len(), 2);
+
+    assert_eq!(requests[1].url, "https://api.api.ai/v1/intents/9b0e5c3a-1d7f-4b28-a6e9-3f5c8d1b7e24?v=20150910");
+    let updated : serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+    assert_eq!(updated["priority"].as_i64(), Option::Some(750000));
+}
@@ -0,0 +1,189 @@
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/**
+* A tiny single-threaded HTTP server used to exercise the real transports without reaching api.ai.
+*
+* Each canned response is served to one incoming connection, in order, and the raw requests that
+* were received are handed back when the server is finished.
+*/
+pub struct MockServer {
+    pub url: String,
+    handle: JoinHandle<Vec<String>>,
+}
+
+impl MockServer {
+
+    pub fn start(responses: Vec<String>) -> MockServer {
+        MockServer::start_delayed(responses, Duration::from_millis(0))
+    }
+
+    /**
+    * Like start, but waits before replying to each request.
+    */
+    pub fn start_delayed(responses: Vec<String>, delay: Duration) -> MockServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                requests.push(read_request(&mut stream));
+                thread::sleep(delay);
+                // the client may have given up waiting, so a failed write is not an error here
+                let _ = stream.write_all(response.as_bytes());
+            }
+
+            requests
+        });
+
+        MockServer{ url: url, handle: handle }
+    }
+
+    /**
+    * Like start, but sends the body of each response a byte at a time, pausing for `interval`
+    * before each byte.
+    */
+    pub fn start_trickled(responses: Vec<String>, interval: Duration) -> MockServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                requests.push(read_request(&mut stream));
+
+                let split = response.find("\r\n\r\n").map(|end| end + 4).unwrap_or(0);
+                let (head, body) = response.as_bytes().split_at(split);
+                if stream.write_all(head).is_err() {
+                    continue;
+                }
+                for byte in body {
+                    thread::sleep(interval);
+                    if stream.write_all(&[*byte]).is_err() {
+                        break;
+                    }
+                }
+            }
+
+            requests
+        });
+
+        MockServer{ url: url, handle: handle }
+    }
+
+    /**
+    * Wait for every canned response to be served and return the raw requests received.
+    */
+    pub fn requests(self) -> Vec<String> {
+        self.handle.join().unwrap()
+    }
+}
+
+/**
+* A single-connection HTTP CONNECT proxy that tunnels through to `upstream` whatever the requested
+* target, or answers with `refusal` instead if one is given.
+*/
+pub struct MockProxy {
+    pub url: String,
+    handle: JoinHandle<String>,
+}
+
+impl MockProxy {
+
+    pub fn start(upstream: &str, refusal: Option<String>) -> MockProxy {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let upstream = String::from(upstream.trim_left_matches("http://"));
+
+        let handle = thread::spawn(move || {
+            let (mut client, _) = listener.accept().unwrap();
+
+            let mut head = Vec::new();
+            let mut byte = [0; 1];
+            while !head.ends_with(b"\r\n\r\n") && client.read(&mut byte).unwrap() > 0 {
+                head.push(byte[0]);
+            }
+
+            if let Option::Some(refusal) = refusal {
+                let _ = client.write_all(refusal.as_bytes());
+                return String::from_utf8_lossy(&head).into_owned();
+            }
+
+            let server = TcpStream::connect(upstream.as_str()).unwrap();
+            client.write_all(b"HTTP/1.1 200 Connection established\r\n\r\n").unwrap();
+
+            let (mut client_read, mut server_write) = (client.try_clone().unwrap(), server.try_clone().unwrap());
+            let forward = thread::spawn(move || { let _ = io::copy(&mut client_read, &mut server_write); });
+            let (mut server_read, mut client_write) = (server, client);
+            let _ = io::copy(&mut server_read, &mut client_write);
+            drop(forward);
+
+            String::from_utf8_lossy(&head).into_owned()
+        });
+
+        MockProxy{ url: url, handle: handle }
+    }
+
+    /**
+    * Wait for the tunnel to close and return the CONNECT request that was received.
+    */
+    pub fn connect_request(self) -> String {
+        self.handle.join().unwrap()
+    }
+}
+
+/**
+* Build a raw HTTP response carrying a JSON body.
+*/
+pub fn json_response(status: u16, body: &str) -> String {
+    format!("HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status, body.len(), body)
+}
+
+pub fn read_request<R: Read>(stream: &mut R) -> String {
+    let mut raw = Vec::new();
+    let mut buf = [0; 4096];
+
+    loop {
+        let n = stream.read(&mut buf).unwrap();
+        if n == 0 {
+            break;
+        }
+        raw.extend_from_slice(&buf[..n]);
+
+        let text = String::from_utf8_lossy(&raw).into_owned();
+        if let Option::Some(end) = text.find("\r\n\r\n") {
+            if text[..end].to_lowercase().contains("transfer-encoding: chunked") {
+                if raw.ends_with(b"\r\n0\r\n\r\n") {
+                    break;
+                }
+                continue;
+            }
+
+            let length = text[..end].lines()
+                .filter_map(|line| {
+                    let mut parts = line.splitn(2, ':');
+                    match (parts.next(), parts.next()) {
+                        (Option::Some(name), Option::Some(value)) if name.eq_ignore_ascii_case("content-length") =>
+                            value.trim().parse::<usize>().ok(),
+                        _ => Option::None
+                    }
+                })
+                .next()
+                .unwrap_or(0);
+
+            if raw.len() >= end + 4 + length {
+                break;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&raw).into_owned()
+}
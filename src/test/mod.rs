@@ -1,2 +1,23 @@
+mod mock_server;
+mod fixtures;
 mod serialization_tests;
 mod http_tests;
+mod transport_tests;
+mod async_tests;
+mod error_tests;
+mod retry_tests;
+mod timeout_tests;
+mod rate_limit_tests;
+mod builder_tests;
+mod config_tests;
+mod proxy_tests;
+mod tls_tests;
+mod voice_tests;
+mod contexts_tests;
+mod entities_tests;
+mod intents_tests;
+mod user_entities_tests;
+mod archive_tests;
+mod v2_tests;
+mod auth_tests;
+mod sys_entities_tests;
@@ -0,0 +1,201 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use builder::ConfigProblem;
+use client::{ApiAIClient,ApiError};
+use rate_limit::{RateLimit, RateLimitMode, RateLimiter};
+use transport::{HttpResponse, MemoryTransport, Timeouts};
+use test::fixtures::{GREETING_RESPONSE, hello};
+
+fn limited_client(transport: Arc<MemoryTransport>, limiter: Arc<RateLimiter>, token: &str) -> ApiAIClient {
+    ApiAIClient{
+        access_token: String::from(token),
+        transport: transport,
+        rate_limiter: Option::Some(limiter),
+        ..Default::default()
+    }
+}
+
+/**
+* Test that going over the limit fails fast without sending the query
+*
+*/
+#[test]
+fn test_fail_fast_when_limit_exceeded() {
+
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(200, GREETING_RESPONSE);
+    transport.push_json(200, GREETING_RESPONSE);
+
+    let limiter = Arc::new(RateLimiter::new(RateLimit::per_minute(1), RateLimitMode::FailFast).unwrap());
+    let client = limited_client(transport.clone(), limiter, "token-a");
+
+    assert!(client.query(hello()).is_ok());
+
+    let outcome = client.query_with_attempts(hello());
+    assert_eq!(outcome.attempts, 0);
+    match outcome.result {
+        Result::Err(ApiError::RateLimited(details)) => {
+            assert_eq!(details.error_type, "client_rate_limited");
+            assert!(details.retry_after.is_some());
+        },
+        _ => panic!("Expected a RateLimited error")
+    }
+
+    assert_eq!(transport.requests().len(), 1);
+}
+
+/**
+* Test that in wait mode the second query is held back until the bucket refills
+*
+*/
+#[test]
+fn test_wait_for_token() {
+
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(200, GREETING_RESPONSE);
+    transport.push_json(200, GREETING_RESPONSE);
+
+    let limit = RateLimit{ requests: 1, per: Duration::from_millis(100) };
+    let limiter = Arc::new(RateLimiter::new(limit, RateLimitMode::Wait).unwrap());
+    let client = limited_client(transport.clone(), limiter, "token-a");
+
+    let started = Instant::now();
+    assert!(client.query(hello()).is_ok());
+    assert!(client.query(hello()).is_ok());
+
+    assert!(started.elapsed() >= Duration::from_millis(90));
+}
+
+/**
+* Test that clones share the limiter while different tokens get separate buckets
+*
+*/
+#[test]
+fn test_limits_shared_between_clones_per_token() {
+
+    let transport = Arc::new(MemoryTransport::new());
+    for _ in 0..3 {
+        transport.push_json(200, GREETING_RESPONSE);
+    }
+
+    let limiter = Arc::new(RateLimiter::new(RateLimit::per_minute(1), RateLimitMode::FailFast).unwrap());
+    limiter.set_token_limit("token-b", RateLimit::per_minute(2)).unwrap();
+
+    let client_a = limited_client(transport.clone(), limiter.clone(), "token-a");
+    let clone_a = client_a.clone();
+    let client_b = limited_client(transport.clone(), limiter, "token-b");
+
+    assert!(client_a.query(hello()).is_ok());
+    assert!(clone_a.query(hello()).is_err());
+
+    assert!(client_b.query(hello()).is_ok());
+    assert!(client_b.query(hello()).is_ok());
+    assert!(client_b.query(hello()).is_err());
+}
+
+/**
+* Test that a 429 with Retry-After closes the bucket until the wait is over
+*
+*/
+#[test]
+fn test_retry_after_holds_limiter() {
+
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_response(HttpResponse{
+        status: 429,
+        headers: vec![(String::from("Retry-After"), String::from("30"))],
+        body: Vec::new()
+    });
+    transport.push_json(200, GREETING_RESPONSE);
+
+    let limiter = Arc::new(RateLimiter::new(RateLimit::per_second(100), RateLimitMode::FailFast).unwrap());
+    let client = limited_client(transport.clone(), limiter, "token-a");
+
+    match client.query(hello()) {
+        Result::Err(ApiError::RateLimited(details)) => assert_eq!(details.code, 429),
+        _ => panic!("Expected the server's RateLimited error")
+    }
+
+    match client.query(hello()) {
+        Result::Err(ApiError::RateLimited(details)) => {
+            assert_eq!(details.error_type, "client_rate_limited");
+            assert!(details.retry_after.unwrap() > Duration::from_secs(25));
+        },
+        _ => panic!("Expected the limiter to fail fast")
+    }
+
+    assert_eq!(transport.requests().len(), 1);
+}
+
+/**
+* Test that waiting on the limiter gives up once it would pass the request deadline
+*
+*/
+#[test]
+fn test_wait_respects_request_deadline() {
+
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(200, GREETING_RESPONSE);
+    transport.push_json(200, GREETING_RESPONSE);
+
+    let limiter = Arc::new(RateLimiter::new(RateLimit::per_minute(1), RateLimitMode::Wait).unwrap());
+    let mut client = limited_client(transport.clone(), limiter, "token-a");
+    client.timeouts = Timeouts{ request: Option::Some(Duration::from_millis(200)), ..Default::default() };
+
+    assert!(client.query(hello()).is_ok());
+
+    let started = Instant::now();
+    match client.query(hello()) {
+        Result::Err(ApiError::Timeout(_)) => {},
+        _ => panic!("Expected a Timeout error")
+    }
+
+    assert!(started.elapsed() < Duration::from_secs(1));
+    assert_eq!(transport.requests().len(), 1);
+}
+
+/**
+* Test that a huge Retry-After doesn't overflow when closing the bucket
+*
+*/
+#[test]
+fn test_huge_retry_after() {
+
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_response(HttpResponse{
+        status: 429,
+        headers: vec![(String::from("Retry-After"), String::from("18446744073709551615"))],
+        body: Vec::new()
+    });
+
+    let limiter = Arc::new(RateLimiter::new(RateLimit::per_second(100), RateLimitMode::FailFast).unwrap());
+    let client = limited_client(transport.clone(), limiter, "token-a");
+
+    assert!(client.query(hello()).is_err());
+
+    match client.query(hello()) {
+        Result::Err(ApiError::RateLimited(details)) => assert_eq!(details.error_type, "client_rate_limited"),
+        _ => panic!("Expected the limiter to fail fast")
+    }
+}
+
+/**
+* Test that limits allowing no queries are refused instead of waiting forever
+*
+*/
+#[test]
+fn test_zero_limits_rejected() {
+
+    assert_eq!(RateLimiter::new(RateLimit::per_minute(0), RateLimitMode::Wait).err(), Option::Some(ConfigProblem::ZeroRateLimit));
+
+    let limit = RateLimit{ requests: 10, per: Duration::from_secs(0) };
+    assert_eq!(RateLimiter::new(limit.clone(), RateLimitMode::Wait).err(), Option::Some(ConfigProblem::ZeroRateLimit));
+
+    let limiter = RateLimiter::new(RateLimit::per_second(1), RateLimitMode::Wait).unwrap();
+    assert_eq!(limiter.set_token_limit("token-b", limit), Result::Err(ConfigProblem::ZeroRateLimit));
+    assert_eq!(limiter.set_token_limit("token-b", RateLimit::per_second(0)), Result::Err(ConfigProblem::ZeroRateLimit));
+
+    // the refused limits were not stored, so the default still applies
+    limiter.acquire("token-b", Option::Some(Duration::from_secs(1))).unwrap();
+}
@@ -0,0 +1,218 @@
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+use client::{ApiAIClient,ApiError};
+use retry::RetryPolicy;
+use transport::{HttpTransport, HttpRequest, HttpResponse, MemoryTransport};
+use test::fixtures::{GREETING_RESPONSE, hello};
+
+fn fast_policy(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy{
+        max_attempts: max_attempts,
+        base_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(10),
+        ..Default::default()
+    }
+}
+
+fn retrying_client(transport: Arc<MemoryTransport>, max_attempts: u32) -> ApiAIClient {
+    ApiAIClient{
+        transport: transport,
+        retry_policy: fast_policy(max_attempts),
+        ..Default::default()
+    }
+}
+
+/**
+* Test that a 503 is retried and the attempt count is reported
+*
+*/
+#[test]
+fn test_retry_server_error() {
+
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(503, r#"{"status":{"code":503,"errorType":"service_unavailable"}}"#);
+    transport.push_json(200, GREETING_RESPONSE);
+
+    let client = retrying_client(transport.clone(), 3);
+
+    let outcome = client.query_with_attempts(hello());
+
+    assert_eq!(outcome.attempts, 2);
+    assert_eq!(outcome.result.unwrap().result.action, "smalltalk.greetings");
+    assert_eq!(transport.requests().len(), 2);
+}
+
+/**
+* Test that client errors are never retried
+*
+*/
+#[test]
+fn test_no_retry_on_client_error() {
+
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(401, r#"{"status":{"code":401,"errorType":"unauthorized"}}"#);
+    transport.push_json(200, GREETING_RESPONSE);
+
+    let client = retrying_client(transport.clone(), 3);
+
+    let outcome = client.query_with_attempts(hello());
+
+    assert_eq!(outcome.attempts, 1);
+    match outcome.result {
+        Result::Err(ApiError::Unauthorized(_)) => (),
+        _ => panic!("Expected an Unauthorized error")
+    }
+}
+
+/**
+* Test that the last error is returned once all attempts are used up
+*
+*/
+#[test]
+fn test_retry_gives_up_after_max_attempts() {
+
+    let transport = Arc::new(MemoryTransport::new());
+    for _ in 0..3 {
+        transport.push_json(502, "Bad Gateway");
+    }
+
+    let client = retrying_client(transport.clone(), 3);
+
+    let outcome = client.query_with_attempts(hello());
+
+    assert_eq!(outcome.attempts, 3);
+    match outcome.result {
+        Result::Err(ApiError::ServerError(details)) => assert_eq!(details.code, 502),
+        _ => panic!("Expected a ServerError")
+    }
+}
+
+/**
+* Test that a 429 is retried and its Retry-After header honoured
+*
+*/
+#[test]
+fn test_retry_rate_limited_with_retry_after() {
+
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_response(HttpResponse{
+        status: 429,
+        headers: vec![(String::from("Retry-After"), String::from("0"))],
+        body: br#"{"status":{"code":429,"errorType":"too_many_requests"}}"#.to_vec()
+    });
+    transport.push_json(200, GREETING_RESPONSE);
+
+    let client = retrying_client(transport.clone(), 2);
+
+    let outcome = client.query_with_attempts(hello());
+
+    assert_eq!(outcome.attempts, 2);
+    assert!(outcome.result.is_ok());
+}
+
+/**
+* Test that a 429 without Retry-After is not retried
+*
+*/
+#[test]
+fn test_no_retry_rate_limited_without_retry_after() {
+
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(429, r#"{"status":{"code":429,"errorType":"too_many_requests"}}"#);
+    transport.push_json(200, GREETING_RESPONSE);
+
+    let client = retrying_client(transport.clone(), 3);
+
+    let outcome = client.query_with_attempts(hello());
+
+    assert_eq!(outcome.attempts, 1);
+    match outcome.result {
+        Result::Err(ApiError::RateLimited(details)) => assert!(details.retry_after.is_none()),
+        _ => panic!("Expected a RateLimited error")
+    }
+    assert_eq!(transport.requests().len(), 1);
+}
+
+/**
+* Test that a Retry-After beyond the maximum delay stops retries
+*
+*/
+#[test]
+fn test_retry_after_beyond_max_delay() {
+
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_response(HttpResponse{
+        status: 429,
+        headers: vec![(String::from("Retry-After"), String::from("3600"))],
+        body: Vec::new()
+    });
+
+    let client = retrying_client(transport.clone(), 3);
+
+    let outcome = client.query_with_attempts(hello());
+
+    assert_eq!(outcome.attempts, 1);
+    match outcome.result {
+        Result::Err(ApiError::RateLimited(details)) => assert_eq!(details.retry_after, Option::Some(Duration::from_secs(3600))),
+        _ => panic!("Expected a RateLimited error")
+    }
+}
+
+/**
+* Transport whose connection is reset on the first call
+*/
+struct ResetOnceTransport(MemoryTransport);
+
+impl HttpTransport for ResetOnceTransport {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse, ApiError> {
+        if self.0.requests().is_empty() {
+            let _ = self.0.send(request);
+            return Result::Err(ApiError::Io(io::Error::new(io::ErrorKind::ConnectionReset, "connection reset")));
+        }
+        self.0.send(request)
+    }
+}
+
+/**
+* Test that a connection reset is retried
+*
+*/
+#[test]
+fn test_retry_connection_reset() {
+
+    let inner = MemoryTransport::new();
+    inner.push_json(200, GREETING_RESPONSE);
+    inner.push_json(200, GREETING_RESPONSE);
+
+    let client = ApiAIClient{
+        transport: Arc::new(ResetOnceTransport(inner)),
+        retry_policy: fast_policy(2),
+        ..Default::default()
+    };
+
+    let outcome = client.query_with_attempts(hello());
+
+    assert_eq!(outcome.attempts, 2);
+    assert!(outcome.result.is_ok());
+}
+
+/**
+* Test that the backoff doubles on each attempt and is capped at the maximum delay
+*
+*/
+#[test]
+fn test_exponential_backoff() {
+
+    let policy = RetryPolicy{
+        base_delay: Duration::from_millis(100),
+        max_delay: Duration::from_millis(500),
+        ..RetryPolicy::exponential(5)
+    };
+
+    assert_eq!(policy.backoff(1), Duration::from_millis(100));
+    assert_eq!(policy.backoff(2), Duration::from_millis(200));
+    assert_eq!(policy.backoff(3), Duration::from_millis(400));
+    assert_eq!(policy.backoff(4), Duration::from_millis(500));
+}
@@ -1,16 +1,22 @@
-use std::collections::HashMap;
 use std::option::Option;
 
 use lang::Language;
 use client::{
     ApiRequest,
     ApiEvent,
+    ApiLocation,
+    ApiOriginalRequest,
     ApiResponse,
     ApiFulfillment,
     ApiStatus,
     ApiMetadata,
     ApiResult,
-    ApiMessage
+    ApiMessage,
+    Intent,
+    IntentResponse,
+    UserSays,
+    UserSaysPart,
+    Parameters
 };
 
 use serde_json;
@@ -66,8 +72,8 @@ fn test_serialize_event_no_args() {
 */
 #[test]
 fn test_serialize_event_with_args_json() {
-    let mut data = HashMap::new();
-    data.insert(String::from("client"), String::from("Slack"));
+    let mut data = Parameters::new();
+    data.insert("client", "Slack");
 
     let e = ApiEvent{
         name: String::from("Welcome"),
@@ -94,7 +100,7 @@ fn test_deserialize_apiquery_event_with_args() {
 
     assert_eq!(evt.name, String::from("Welcome"));
 
-    assert_eq!(evt.data.unwrap().get("client").unwrap(), "Slack");
+    assert_eq!(evt.data.unwrap().get_str("client"), Option::Some("Slack"));
 }
 
 /**
@@ -137,6 +143,51 @@ fn test_serialize_apirequest_query(){
 }
 
 
+/**
+* Test that the optional query settings are sent when set
+*
+*/
+#[test]
+fn test_serialize_apirequest_options(){
+
+    let query_string = r#"{"query":"book a table tomorrow at 8","sessionId":"12345","lang":"en","contexts":[],"resetContexts":true,"timezone":"Europe/Paris","location":{"latitude":48.8566,"longitude":2.3522},"originalRequest":{"source":"slack","data":{"channel":"C024BE91L"}}}"#;
+
+    let req = ApiRequest{
+        query: Option::Some(String::from("book a table tomorrow at 8")),
+        session_id: String::from("12345"),
+        reset_contexts: Option::Some(true),
+        timezone: Option::Some(String::from("Europe/Paris")),
+        location: Option::Some(ApiLocation{ latitude: 48.8566, longitude: 2.3522 }),
+        original_request: Option::Some(ApiOriginalRequest{
+            source: String::from("slack"),
+            data: Option::Some(serde_json::from_str(r#"{"channel":"C024BE91L"}"#).unwrap())
+        }),
+        ..Default::default()
+    };
+
+    assert_eq!(query_string, serde_json::to_string(&req).unwrap());
+}
+
+/**
+* Test that a request with the optional query settings can be read back
+*
+*/
+#[test]
+fn test_deserialize_apirequest_options(){
+
+    let query_string = r#"{"query":"hello","sessionId":"12345","lang":"en","contexts":[],"timezone":"America/New_York","location":{"latitude":40.7128,"longitude":-74.006},"originalRequest":{"source":"facebook"}}"#;
+
+    let req : ApiRequest = serde_json::from_str(query_string).unwrap();
+
+    assert!(req.reset_contexts.is_none());
+    assert_eq!(req.timezone.unwrap(), "America/New_York");
+    assert_eq!(req.location, Option::Some(ApiLocation{ latitude: 40.7128, longitude: -74.006 }));
+
+    let original = req.original_request.unwrap();
+    assert_eq!(original.source, "facebook");
+    assert!(original.data.is_none());
+}
+
 /**
 * Test that deserializing an event with no args works as expected
 *
@@ -196,7 +247,7 @@ fn test_deserialize_apirequest_event_with_data(){
     assert_eq!(event.name, "Welcome");
     let data = event.data.unwrap();
 
-    assert_eq!(data.get("test").unwrap(), "arg1");
+    assert_eq!(data.get_str("test"), Option::Some("arg1"));
 }
 
 
@@ -225,9 +276,10 @@ fn test_deserialize_apimessage_text(){
     let msg : ApiMessage = serde_json::from_str(&msg_string).unwrap();
 
     match msg {
-        ApiMessage::Text{speech, message_type} => {
-            assert_eq!(speech, String::from("Hello"));
-            assert_eq!(message_type, 0);
+        ApiMessage::Text(text) => {
+            assert_eq!(text.first_speech(), Option::Some("Hello"));
+            assert!(!text.speech_list);
+            assert!(text.platform.is_none());
         },
 
         _ => panic!("Type is suppsed to be text")
@@ -235,6 +287,35 @@ fn test_deserialize_apimessage_text(){
 
 }
 
+/**
+* Test that a text message with a list of speech variants, as intents hold, is read as text and
+* written back as a list
+*
+*/
+#[test]
+fn test_apimessage_text_speech_variants(){
+
+    let msg_string = r#"{"speech":["Hi!","Hello there!"],"type":0}"#;
+    let msg : ApiMessage = serde_json::from_str(&msg_string).unwrap();
+
+    match msg {
+        ApiMessage::Text(ref text) => {
+            assert_eq!(text.speech, vec![String::from("Hi!"), String::from("Hello there!")]);
+            assert_eq!(text.first_speech(), Option::Some("Hi!"));
+        },
+        _ => panic!("Type is supposed to be text")
+    };
+    assert_eq!(msg_string, serde_json::to_string(&msg).unwrap());
+
+    let single = r#"{"speech":["Hi!"],"type":0}"#;
+    let msg : ApiMessage = serde_json::from_str(&single).unwrap();
+    assert_eq!(single, serde_json::to_string(&msg).unwrap());
+
+    let empty = r#"{"speech":[],"type":0}"#;
+    let msg : ApiMessage = serde_json::from_str(&empty).unwrap();
+    assert_eq!(empty, serde_json::to_string(&msg).unwrap());
+}
+
 #[test]
 fn test_deserialize_api_response(){
     let json_string = r#"{
@@ -287,21 +368,24 @@ fn test_serialize_api_response(){
     let status = ApiStatus{
         code: 200,
         error_type: String::from("success"),
-        error_details: Option::None
+        error_details: Option::None,
+        ..Default::default()
     };
 
     let msg = ApiMessage::new_text(String::from("Hi Sam! Nice to meet you!"));
 
     let fulfillment = ApiFulfillment {
         speech: String::from("Hi Sam! Nice to meet you!"),
-        messages: Option::Some(vec!(msg))
+        messages: Option::Some(vec!(msg)),
+        ..Default::default()
     };
 
     let metadata = ApiMetadata{
         intent_id: Option::Some(String::from("9f41ef7c-82fa-42a7-9a30-49a93e2c14d0")),
         webhook_used: String::from("false"),
         webhook_slotfilling_used: String::from("false"),
-        intent_name: Option::Some(String::from("greetings"))
+        intent_name: Option::Some(String::from("greetings")),
+        ..Default::default()
     };
 
     let result = ApiResult{
@@ -309,11 +393,12 @@ fn test_serialize_api_response(){
         resolved_query: String::from("My name is Sam and I live in Paris"),
         action: String::from("greetings"),
         action_incomplete: false,
-        parameters: HashMap::new(),
+        parameters: Parameters::new(),
         contexts: Vec::new(),
         metadata: metadata,
         fulfillment: fulfillment,
         score: 1.0,
+        ..Default::default()
     };
 
     let response = ApiResponse{
@@ -322,7 +407,8 @@ fn test_serialize_api_response(){
         lang: Language::English,
         result: result,
         status: status,
-        session_id: String::from("4b6a6779-b8ea-4094-b2ed-a302ba201815")
+        session_id: String::from("4b6a6779-b8ea-4094-b2ed-a302ba201815"),
+        ..Default::default()
     };
 
     let ideal_output = r#"{"id":"b340a1f7-abee-4e13-9bdd-5e8938a48b7d","timestamp":"2017-02-09T15:38:26.548Z","lang":"en","result":{"source":"agent","resolvedQuery":"My name is Sam and I live in Paris","action":"greetings","actionIncomplete":false,"parameters":{},"contexts":[],"metadata":{"intentId":"9f41ef7c-82fa-42a7-9a30-49a93e2c14d0","webhookUsed":"false","webhookForSlotFillingUsed":"false","intentName":"greetings"},"fulfillment":{"speech":"Hi Sam! Nice to meet you!","messages":[{"speech":"Hi Sam! Nice to meet you!","type":0}]},"score":1.0},"status":{"code":200,"errorType":"success"},"sessionId":"4b6a6779-b8ea-4094-b2ed-a302ba201815"}"#;
@@ -330,3 +416,353 @@ fn test_serialize_api_response(){
     //println!("{}", serde_json::to_string(&response).unwrap());
     assert_eq!(ideal_output, serde_json::to_string(&response).unwrap())
 }
+
+/**
+* Test that an intent as exported by api.ai survives a round trip through the intent types
+* without losing anything.
+*
+*/
+#[test]
+fn test_intent_round_trip(){
+
+    let json_string = include_str!("fixtures/intent.json");
+
+    let intent : Intent = serde_json::from_str(json_string).unwrap();
+
+    assert_eq!(intent.name, "weather.forecast");
+    assert_eq!(intent.user_says[0].data[1].alias.as_ref().unwrap(), "city");
+    assert_eq!(intent.responses[0].parameters[0].default_value.as_ref().unwrap(), "London");
+    assert_eq!(intent.events[0].name, "WEATHER");
+
+    let original : serde_json::Value = serde_json::from_str(json_string).unwrap();
+    let round_tripped : serde_json::Value = serde_json::from_str(&serde_json::to_string(&intent).unwrap()).unwrap();
+
+    assert_eq!(original, round_tripped);
+}
+
+/**
+* Test that a new intent leaves out the fields api.ai fills in
+*
+*/
+#[test]
+fn test_serialize_new_intent(){
+
+    let intent = Intent{
+        id: Option::None,
+        name: String::from("greeting"),
+        auto: true,
+        contexts: Vec::new(),
+        templates: Vec::new(),
+        user_says: vec![UserSays{
+            id: Option::None,
+            data: vec![UserSaysPart{ text: String::from("hi"), ..Default::default() }],
+            is_template: false,
+            count: 0,
+            ..Default::default()
+        }],
+        responses: vec![IntentResponse{
+            action: Option::None,
+            reset_contexts: false,
+            affected_contexts: Vec::new(),
+            parameters: Vec::new(),
+            messages: vec![ApiMessage::new_text(String::from("Hello!"))],
+            ..Default::default()
+        }],
+        priority: Option::None,
+        webhook_used: false,
+        webhook_for_slot_filling: false,
+        fallback_intent: false,
+        events: Vec::new(),
+        ..Default::default()
+    };
+
+    let intent_string = r#"{"name":"greeting","auto":true,"contexts":[],"templates":[],"userSays":[{"data":[{"text":"hi"}],"isTemplate":false,"count":0}],"responses":[{"resetContexts":false,"affectedContexts":[],"parameters":[],"messages":[{"speech":"Hello!","type":0}]}],"webhookUsed":false,"webhookForSlotFilling":false,"fallbackIntent":false,"events":[]}"#;
+
+    assert_eq!(intent_string, serde_json::to_string(&intent).unwrap());
+}
+
+#[derive(Deserialize,Debug,PartialEq)]
+struct UnitCurrency {
+    amount: f64,
+    currency: String,
+}
+
+/**
+* Test that parameters which aren't plain strings keep their type and can be read back
+*
+*/
+#[test]
+fn test_deserialize_typed_parameters() {
+
+    let result_string = r#"{"source":"agent","resolvedQuery":"send 20 dollars to Sam and Alex","action":"transfer","actionIncomplete":false,"parameters":{"recipients":["Sam","Alex"],"amount":{"amount":20,"currency":"USD"},"count":"2","urgent":true,"note":""},"contexts":[{"name":"transfer","parameters":{"count":2},"lifespan":2}],"metadata":{},"fulfillment":{"speech":""},"score":0.9}"#;
+
+    let result : ApiResult = serde_json::from_str(result_string).unwrap();
+
+    let recipients = result.parameters.get_list("recipients").unwrap();
+    assert_eq!(recipients.len(), 2);
+    assert_eq!(recipients[1].as_str(), Option::Some("Alex"));
+
+    assert_eq!(result.parameters.get_as::<UnitCurrency>("amount"), Option::Some(UnitCurrency{ amount: 20.0, currency: String::from("USD") }));
+    assert_eq!(result.parameters.get_as::<Vec<String>>("recipients"), Option::Some(vec![String::from("Sam"), String::from("Alex")]));
+    assert_eq!(result.parameters.get_number("count"), Option::Some(2.0));
+    assert_eq!(result.parameters.get_as::<bool>("urgent"), Option::Some(true));
+    assert_eq!(result.parameters.get_str("note"), Option::Some(""));
+
+    assert!(result.parameters.get_str("recipients").is_none());
+    assert!(result.parameters.get_number("note").is_none());
+    assert!(result.parameters.get_as::<UnitCurrency>("missing").is_none());
+
+    assert_eq!(result.contexts[0].parameters.get_number("count"), Option::Some(2.0));
+}
+
+/**
+* Test that typed parameters are written back out unchanged
+*
+*/
+#[test]
+fn test_serialize_typed_parameters() {
+
+    let mut data = Parameters::new();
+    data.insert("guests", 4);
+    data.insert("tags", vec!["vegan", "quiet"]);
+
+    let e = ApiEvent{
+        name: String::from("BOOK"),
+        data: Option::Some(data)
+    };
+
+    let value = serde_json::to_value(&e).unwrap();
+    assert_eq!(value, serde_json::from_str::<serde_json::Value>(r#"{"name":"BOOK","data":{"guests":4,"tags":["vegan","quiet"]}}"#).unwrap());
+}
+
+/**
+* Test that every kind of message is recognised by its type, and that a type we don't know
+* doesn't stop the rest of the fulfillment from being read
+*
+*/
+#[test]
+fn test_deserialize_all_message_types() {
+
+    let fulfillment_string = r#"{"speech":"Here's the forecast","messages":[
+        {"type":0,"platform":"facebook","speech":"Here's the forecast"},
+        {"type":1,"platform":"facebook","title":"London","subtitle":"Rain later","imageUrl":"https://example.com/rain.png","buttons":[{"text":"Tomorrow","postback":"weather tomorrow"}]},
+        {"type":2,"platform":"telegram","title":"Anything else?","replies":["Tomorrow","Next week"]},
+        {"type":3,"imageUrl":"https://example.com/rain.png"},
+        {"type":4,"platform":"slack","payload":{"slack":{"text":"*Rain* later"}}},
+        {"type":"simple_response","platform":"google","textToSpeech":"Rain later","displayText":"Rain later today"},
+        {"type":"basic_card","platform":"google","title":"London","formattedText":"Rain *later*","image":{"url":"https://example.com/rain.png","accessibilityText":"rain"},"buttons":[{"title":"Details","openUrlAction":{"url":"https://example.com/london"}}]},
+        {"type":"suggestion_chips","platform":"google","suggestions":[{"title":"Tomorrow"},{"title":"Next week"}]},
+        {"type":"link_out_chip","platform":"google","destinationName":"Met Office","url":"https://example.com/met"},
+        {"type":"list_card","platform":"google","title":"Cities","items":[{"optionInfo":{"key":"london","synonyms":["ldn"]},"title":"London"}]},
+        {"type":"carousel_card","platform":"google","items":[{"optionInfo":{"key":"paris","synonyms":[]},"title":"Paris","description":"Sunny"}]},
+        {"type":"hologram","platform":"future","shape":"cloud"}
+    ]}"#;
+
+    let fulfillment : ApiFulfillment = serde_json::from_str(fulfillment_string).unwrap();
+    let messages = fulfillment.messages.unwrap();
+    assert_eq!(messages.len(), 12);
+
+    match messages[1] {
+        ApiMessage::Card(ref card) => {
+            assert_eq!(card.title, "London");
+            assert_eq!(card.buttons[0].postback.as_ref().unwrap(), "weather tomorrow");
+        },
+        _ => panic!("expected a card")
+    }
+    match messages[2] {
+        ApiMessage::QuickReplies(ref replies) => assert_eq!(replies.replies, vec!["Tomorrow", "Next week"]),
+        _ => panic!("expected quick replies")
+    }
+    match messages[3] {
+        ApiMessage::Image(ref image) => assert_eq!(image.image_url, "https://example.com/rain.png"),
+        _ => panic!("expected an image")
+    }
+    match messages[4] {
+        ApiMessage::CustomPayload(ref payload) => assert_eq!(payload.payload["slack"]["text"], "*Rain* later"),
+        _ => panic!("expected a custom payload")
+    }
+    match messages[5] {
+        ApiMessage::SimpleResponse(ref response) => assert_eq!(response.display_text.as_ref().unwrap(), "Rain later today"),
+        _ => panic!("expected a simple response")
+    }
+    match messages[6] {
+        ApiMessage::BasicCard(ref card) => assert_eq!(card.buttons[0].open_url_action.url, "https://example.com/london"),
+        _ => panic!("expected a basic card")
+    }
+    match messages[7] {
+        ApiMessage::SuggestionChips(ref chips) => assert_eq!(chips.suggestions[1].title, "Next week"),
+        _ => panic!("expected suggestion chips")
+    }
+    match messages[8] {
+        ApiMessage::LinkOutChip(ref chip) => assert_eq!(chip.destination_name, "Met Office"),
+        _ => panic!("expected a link out chip")
+    }
+    match messages[9] {
+        ApiMessage::ListCard(ref list) => assert_eq!(list.items[0].option_info.synonyms, vec!["ldn"]),
+        _ => panic!("expected a list")
+    }
+    match messages[10] {
+        ApiMessage::CarouselCard(ref carousel) => assert_eq!(carousel.items[0].description.as_ref().unwrap(), "Sunny"),
+        _ => panic!("expected a carousel")
+    }
+    match messages[11] {
+        ApiMessage::Unknown(ref value) => assert_eq!(value["shape"], "cloud"),
+        _ => panic!("expected an unknown message")
+    }
+
+    let platforms: Vec<Option<&str>> = messages.iter().map(ApiMessage::platform).collect();
+    assert_eq!(platforms, vec![Option::Some("facebook"), Option::Some("facebook"), Option::Some("telegram"), Option::None,
+                               Option::Some("slack"), Option::Some("google"), Option::Some("google"), Option::Some("google"),
+                               Option::Some("google"), Option::Some("google"), Option::Some("google"), Option::Some("future")]);
+
+    let written = serde_json::to_value(&ApiFulfillment{ messages: Option::Some(messages), ..Default::default() }).unwrap();
+    let original = serde_json::from_str::<serde_json::Value>(fulfillment_string).unwrap();
+    assert_eq!(written["messages"], original["messages"]);
+}
+
+/**
+* Test that a message of a known type but the wrong shape is kept as it was sent
+*
+*/
+#[cfg(not(feature = "strict"))]
+#[test]
+fn test_deserialize_malformed_message() {
+
+    let original : serde_json::Value = serde_json::from_str(r#"{"type":3,"platform":"facebook"}"#).unwrap();
+    let message : ApiMessage = serde_json::from_value(original.clone()).unwrap();
+
+    assert_eq!(message, ApiMessage::Unknown(original.clone()));
+    assert_eq!(message.platform(), Option::Some("facebook"));
+    assert_eq!(serde_json::to_value(&message).unwrap(), original);
+}
+
+/**
+* Test that the strict feature reports a message of a known type but the wrong shape
+*
+*/
+#[cfg(feature = "strict")]
+#[test]
+fn test_deserialize_malformed_message() {
+
+    assert!(serde_json::from_str::<ApiMessage>(r#"{"type":3,"platform":"facebook"}"#).is_err());
+}
+
+static RESPONSE_WITH_NEW_FIELDS: &'static str = r#"{"id":"b340a1f7-abee-4e13-9bdd-5e8938a48b7d","timestamp":"2017-02-09T15:38:26.548Z","lang":"en","result":{"source":"agent","resolvedQuery":"Hello!","action":"greetings","actionIncomplete":false,"parameters":{},"contexts":[{"name":"greeted","parameters":{},"lifespan":1,"origin":"intent"}],"metadata":{"intentId":"9f41ef7c-82fa-42a7-9a30-49a93e2c14d0","webhookUsed":"false","webhookForSlotFillingUsed":"false","intentName":"greetings","endConversation":true},"fulfillment":{"speech":"Hi!","displayText":"Hi there!"},"score":1,"sentiment":{"score":0.5}},"status":{"code":200,"errorType":"success","webhookTimedOut":false},"sessionId":"12345","alternativeResultsFromKnowledgeService":{}}"#;
+
+/**
+* Test that fields we don't model are kept, and written back out when the response is serialized
+*
+*/
+#[cfg(not(feature = "strict"))]
+#[test]
+fn test_round_trip_unknown_fields() {
+
+    let response : ApiResponse = serde_json::from_str(RESPONSE_WITH_NEW_FIELDS).unwrap();
+
+    assert_eq!(response.result.score, 1.0);
+    assert!(response.extra.contains_key("alternativeResultsFromKnowledgeService"));
+    assert_eq!(response.result.extra["sentiment"]["score"], 0.5);
+    assert_eq!(response.result.metadata.extra["endConversation"].as_bool(), Option::Some(true));
+    assert_eq!(response.result.fulfillment.extra["displayText"], "Hi there!");
+    assert_eq!(response.result.contexts[0].extra["origin"], "intent");
+    assert_eq!(response.status.extra["webhookTimedOut"].as_bool(), Option::Some(false));
+
+    let written = serde_json::to_value(&response).unwrap();
+    let mut original = serde_json::from_str::<serde_json::Value>(RESPONSE_WITH_NEW_FIELDS).unwrap();
+    original["result"]["score"] = serde_json::Value::from(1.0);
+
+    assert_eq!(written, original);
+}
+
+/**
+* Test that missing or mistyped fields are given defaults instead of failing the whole response
+*
+*/
+#[cfg(not(feature = "strict"))]
+#[test]
+fn test_deserialize_incomplete_response() {
+
+    let response_string = r#"{"id":"b340a1f7","timestamp":"2017-02-09T15:38:26.548Z","result":{"source":"agent","resolvedQuery":"Hello!","parameters":{},"fulfillment":{"speech":"Hi!"},"score":"high"},"sessionId":"12345"}"#;
+
+    let response : ApiResponse = serde_json::from_str(response_string).unwrap();
+
+    assert_eq!(response.lang, Language::English);
+    assert_eq!(response.result.action, "");
+    assert_eq!(response.result.score, 0.0);
+    assert_eq!(response.result.metadata.webhook_used, "false");
+    assert!(response.result.metadata.intent_id.is_none());
+    assert_eq!(response.status.code, 200);
+    assert_eq!(response.result.fulfillment.speech, "Hi!");
+
+    // the unreadable score is kept and written back as it was sent
+    assert_eq!(response.result.extra["score"], "high");
+    let written = serde_json::to_value(&response).unwrap();
+    assert_eq!(written["result"]["score"], "high");
+}
+
+/**
+* Test that the strict feature turns unknown, missing and mistyped fields into errors
+*
+*/
+#[cfg(feature = "strict")]
+#[test]
+fn test_strict_deserialization() {
+
+    let err = serde_json::from_str::<ApiResponse>(RESPONSE_WITH_NEW_FIELDS).err().unwrap();
+    assert!(err.to_string().contains("unknown fields"));
+
+    let err = serde_json::from_str::<ApiStatus>(r#"{"code":200}"#).err().unwrap();
+    assert!(err.to_string().contains("missing field `errorType`"));
+
+    let err = serde_json::from_str::<ApiStatus>(r#"{"code":{"http":200},"errorType":"success"}"#).err().unwrap();
+    assert!(err.to_string().contains("invalid field `code`"));
+}
+
+static INTENT_WITH_NEW_FIELDS: &'static str = r#"{"id":"2a7c1d04","name":"greeting","auto":true,"contexts":[],"templates":[],"userSays":[{"id":"6e1f4b2a","data":[{"text":"hi","userDefined":false}],"isTemplate":false,"count":0,"updated":1510000000}],"responses":[{"resetContexts":false,"affectedContexts":[],"parameters":[{"name":"city","dataType":"@sys.geo-city","value":"$city","required":true,"isList":false,"prompts":[{"lang":"en","value":"Which city?"}]}],"messages":[{"type":0,"lang":"en","speech":"Hello!"}],"defaultResponsePlatforms":{"google":true},"speech":[]}],"priority":500000,"webhookUsed":false,"webhookForSlotFilling":false,"fallbackIntent":false,"events":[],"lastUpdate":1510000000,"parentId":"9f41ef7c","rootParentId":"9f41ef7c"}"#;
+
+/**
+* Test that the intent and entity types keep the fields they don't model, including values they
+* can't read, and write them back out
+*/
+#[cfg(not(feature = "strict"))]
+#[test]
+fn test_management_types_keep_unknown_fields() {
+    use client::{Entity, EntitySummary, IntentSummary, UserEntity};
+
+    let intent : Intent = serde_json::from_str(INTENT_WITH_NEW_FIELDS).unwrap();
+
+    assert_eq!(intent.extra["lastUpdate"], 1510000000);
+    assert_eq!(intent.extra["parentId"], "9f41ef7c");
+    assert_eq!(intent.user_says[0].extra["updated"], 1510000000);
+    assert_eq!(intent.responses[0].extra["defaultResponsePlatforms"]["google"].as_bool(), Option::Some(true));
+    assert!(intent.responses[0].parameters[0].prompts.is_empty());
+    assert_eq!(intent.responses[0].parameters[0].extra["prompts"][0]["value"], "Which city?");
+
+    match intent.responses[0].messages[0] {
+        ApiMessage::Text(ref text) => assert_eq!(text.extra["lang"], "en"),
+        _ => panic!("Expected a text message")
+    }
+
+    let original : serde_json::Value = serde_json::from_str(INTENT_WITH_NEW_FIELDS).unwrap();
+    assert_eq!(serde_json::to_value(&intent).unwrap(), original);
+
+    let summary_string = r#"{"id":"2a7c1d04","name":"greeting","contextIn":[],"contextOut":[],"events":[],"parameters":[],"actions":["greet"],"fallbackIntent":false,"lastUpdate":1510000000}"#;
+    let summary : IntentSummary = serde_json::from_str(summary_string).unwrap();
+    assert_eq!(summary.extra["lastUpdate"], 1510000000);
+    assert_eq!(serde_json::to_value(&summary).unwrap(), serde_json::from_str::<serde_json::Value>(summary_string).unwrap());
+
+    let entity_string = r#"{"id":"d1c3a8b2","name":"product","entries":[{"value":"tee","synonyms":["tee"]}],"isEnum":false,"automatedExpansion":false,"isOverridable":true,"allowFuzzyExtraction":false}"#;
+    let entity : Entity = serde_json::from_str(entity_string).unwrap();
+    assert_eq!(entity.extra["isOverridable"].as_bool(), Option::Some(true));
+    assert_eq!(serde_json::to_value(&entity).unwrap(), serde_json::from_str::<serde_json::Value>(entity_string).unwrap());
+
+    let summary_string = r#"{"id":"d1c3a8b2","name":"product","count":1,"preview":"tee","isOverridable":true}"#;
+    let summary : EntitySummary = serde_json::from_str(summary_string).unwrap();
+    assert_eq!(summary.extra["isOverridable"].as_bool(), Option::Some(true));
+    assert_eq!(serde_json::to_value(&summary).unwrap(), serde_json::from_str::<serde_json::Value>(summary_string).unwrap());
+
+    let user_entity_string = r#"{"sessionId":"12345","name":"address","extend":false,"entries":[],"lifespan":10}"#;
+    let user_entity : UserEntity = serde_json::from_str(user_entity_string).unwrap();
+    assert_eq!(user_entity.extra["lifespan"], 10);
+    assert_eq!(serde_json::to_value(&user_entity).unwrap(), serde_json::from_str::<serde_json::Value>(user_entity_string).unwrap());
+}
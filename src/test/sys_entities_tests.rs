@@ -0,0 +1,89 @@
+use chrono::{Duration, NaiveDate, NaiveTime, TimeZone, UTC};
+
+use client::ApiResult;
+use sys_entities::{DatePeriod, SysDate, SysDateTime, SysNumber, SysTime, TimePeriod, UnitAmount, UnitCurrency};
+
+use serde_json;
+
+static RESULT: &'static str = r#"{
+  "source": "agent",
+  "resolvedQuery": "book a table for 4 on March 10th at 7:30pm for two hours, budget 50 euros",
+  "action": "table.book",
+  "actionIncomplete": false,
+  "parameters": {
+    "date": "2017-03-10",
+    "time": "19:30:00",
+    "date-time": "2017-03-10T19:30:00Z",
+    "date-period": "2017-03-01/2017-03-31",
+    "time-period": "18:00:00/22:00:00",
+    "guests": "4",
+    "count": 4,
+    "budget": {"amount": 50, "currency": "EUR"},
+    "length": {"amount": 2, "unit": "h"},
+    "age": {"amount": "30", "unit": "year"},
+    "city": "Paris",
+    "missing": ""
+  },
+  "contexts": [],
+  "metadata": {},
+  "fulfillment": {"speech": ""},
+  "score": 1.0
+}"#;
+
+/**
+* Test that each system entity value is decoded into its type through ApiResult::param
+*
+*/
+#[test]
+fn test_decode_system_entities() {
+
+    let result: ApiResult = serde_json::from_str(RESULT).unwrap();
+
+    assert_eq!(result.param::<SysDate>("date"), Option::Some(SysDate(NaiveDate::from_ymd(2017, 3, 10))));
+    assert_eq!(result.param::<SysTime>("time"), Option::Some(SysTime(NaiveTime::from_hms(19, 30, 0))));
+    assert_eq!(result.param::<SysDateTime>("date-time"), Option::Some(SysDateTime(UTC.ymd(2017, 3, 10).and_hms(19, 30, 0))));
+
+    let period = result.param::<DatePeriod>("date-period").unwrap();
+    assert_eq!(period.start, NaiveDate::from_ymd(2017, 3, 1));
+    assert_eq!(period.end, NaiveDate::from_ymd(2017, 3, 31));
+    assert!(period.contains(NaiveDate::from_ymd(2017, 3, 31)));
+    assert!(!period.contains(NaiveDate::from_ymd(2017, 4, 1)));
+
+    assert_eq!(result.param::<TimePeriod>("time-period"),
+               Option::Some(TimePeriod{ start: NaiveTime::from_hms(18, 0, 0), end: NaiveTime::from_hms(22, 0, 0) }));
+
+    assert_eq!(result.param::<SysNumber>("guests"), Option::Some(SysNumber(4.0)));
+    assert_eq!(result.param::<SysNumber>("count"), Option::Some(SysNumber(4.0)));
+
+    assert_eq!(result.param::<UnitCurrency>("budget"), Option::Some(UnitCurrency{ amount: 50.0, currency: String::from("EUR") }));
+
+    let length = result.param::<UnitAmount>("length").unwrap();
+    assert_eq!(length.to_duration(), Option::Some(Duration::hours(2)));
+
+    let age = result.param::<UnitAmount>("age").unwrap();
+    assert_eq!(age.amount, 30.0);
+    assert!(age.to_duration().is_none());
+
+    assert_eq!(result.param::<String>("city"), Option::Some(String::from("Paris")));
+}
+
+/**
+* Test that empty, missing and malformed values give None rather than an error
+*
+*/
+#[test]
+fn test_undecodable_system_entities() {
+
+    let result: ApiResult = serde_json::from_str(RESULT).unwrap();
+
+    assert!(result.param::<SysDate>("missing").is_none());
+    assert!(result.param::<SysDate>("not-there").is_none());
+    assert!(result.param::<SysDate>("time").is_none());
+    assert!(result.param::<DatePeriod>("date").is_none());
+    assert!(result.param::<SysNumber>("city").is_none());
+    assert!(result.param::<UnitCurrency>("length").is_none());
+
+    assert!(SysDate::parse("10/03/2017").is_none());
+    assert_eq!(SysTime::parse("07:15"), Option::Some(SysTime(NaiveTime::from_hms(7, 15, 0))));
+    assert_eq!(SysDateTime::parse("2017-03-10T19:30:00+01:00"), Option::Some(SysDateTime(UTC.ymd(2017, 3, 10).and_hms(18, 30, 0))));
+}
@@ -0,0 +1,156 @@
+use std::io::{self, Read};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use client::{ApiAIClient,ApiError};
+use transport::{HttpMethod, HttpRequest, HttpTransport, HyperTransport, StreamingBody, Timeouts};
+use test::mock_server::{MockServer, json_response};
+use test::fixtures::{GREETING_RESPONSE, hello};
+
+/**
+* Test that a server that doesn't answer in time produces a Timeout error
+*
+*/
+#[test]
+fn test_read_timeout() {
+
+    let server = MockServer::start_delayed(vec![json_response(200, GREETING_RESPONSE)], Duration::from_millis(500));
+
+    let client = ApiAIClient{
+        base_url: server.url.clone(),
+        timeouts: Timeouts{
+            read: Option::Some(Duration::from_millis(100)),
+            ..Timeouts::none()
+        },
+        ..Default::default()
+    };
+
+    match client.query(hello()) {
+        Result::Err(ApiError::Timeout(_)) => (),
+        _ => panic!("Expected a Timeout error")
+    }
+}
+
+/**
+* Test that the overall deadline also bounds the socket reads
+*
+*/
+#[test]
+fn test_request_deadline() {
+
+    let server = MockServer::start_delayed(vec![json_response(200, GREETING_RESPONSE)], Duration::from_millis(500));
+
+    let client = ApiAIClient{
+        base_url: server.url.clone(),
+        timeouts: Timeouts{
+            request: Option::Some(Duration::from_millis(100)),
+            ..Timeouts::none()
+        },
+        ..Default::default()
+    };
+
+    match client.query(hello()) {
+        Result::Err(ApiError::Timeout(_)) => (),
+        _ => panic!("Expected a Timeout error")
+    }
+}
+
+/**
+* Test that per call timeouts override the client's own
+*
+*/
+#[test]
+fn test_per_call_timeout_override() {
+
+    let server = MockServer::start_delayed(vec![json_response(200, GREETING_RESPONSE)], Duration::from_millis(200));
+
+    let client = ApiAIClient{
+        base_url: server.url.clone(),
+        timeouts: Timeouts{
+            read: Option::Some(Duration::from_millis(50)),
+            ..Timeouts::none()
+        },
+        ..Default::default()
+    };
+
+    let response = client.query_with_timeouts(hello(), Timeouts::default()).unwrap();
+    assert_eq!(response.result.action, "smalltalk.greetings");
+}
+
+/**
+* Test that a server sending its reply slowly, but never slowly enough to trip the read timeout,
+* can't hold a call past the overall deadline
+*
+*/
+#[test]
+fn test_request_deadline_with_trickled_body() {
+
+    let server = MockServer::start_trickled(vec![json_response(200, GREETING_RESPONSE)], Duration::from_millis(20));
+
+    let client = ApiAIClient{
+        base_url: server.url.clone(),
+        timeouts: Timeouts{
+            read: Option::Some(Duration::from_millis(500)),
+            request: Option::Some(Duration::from_millis(300)),
+            ..Timeouts::none()
+        },
+        ..Default::default()
+    };
+
+    let started = Instant::now();
+    match client.query(hello()) {
+        Result::Err(ApiError::Timeout(_)) => (),
+        _ => panic!("Expected a Timeout error")
+    }
+    assert!(started.elapsed() < Duration::from_millis(1000));
+}
+
+/**
+* Request body that takes a while to produce
+*/
+struct SlowBody {
+    delay: Duration,
+    body: &'static [u8],
+}
+
+impl Read for SlowBody {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        thread::sleep(self.delay);
+        self.delay = Duration::from_millis(0);
+        self.body.read(buf)
+    }
+}
+
+/**
+* Test that time spent sending the request comes off the wait for the response head, rather than
+* the head getting a fresh read timeout of its own
+*
+*/
+#[test]
+fn test_request_deadline_after_slow_send() {
+
+    let server = MockServer::start_delayed(vec![json_response(200, GREETING_RESPONSE)], Duration::from_secs(3));
+
+    let request = HttpRequest{
+        method: HttpMethod::Post,
+        url: format!("{}/query", server.url),
+        headers: Vec::new(),
+        body: Vec::new(),
+        timeouts: Timeouts{
+            read: Option::Some(Duration::from_secs(5)),
+            request: Option::Some(Duration::from_millis(500)),
+            ..Timeouts::none()
+        },
+    };
+    let body = StreamingBody{
+        reader: Box::new(SlowBody{ delay: Duration::from_millis(400), body: b"hello" }),
+        length: Option::Some(5),
+    };
+
+    let started = Instant::now();
+    match HyperTransport::new().send_stream(request, body) {
+        Result::Err(ApiError::Timeout(_)) => (),
+        _ => panic!("Expected a Timeout error")
+    }
+    assert!(started.elapsed() < Duration::from_millis(800));
+}
@@ -0,0 +1,486 @@
+use builder::ConfigProblem;
+use client::ApiAIClient;
+use tls::{Certificate, ClientIdentity, TlsConfig, pem_certificates};
+use test::fixtures::TOKEN;
+
+static CA_PEM: &'static [u8] = include_bytes!("fixtures/ca.pem");
+static SERVER_PEM: &'static [u8] = include_bytes!("fixtures/server.pem");
+static CLIENT_PEM: &'static [u8] = include_bytes!("fixtures/client.pem");
+static CLIENT_KEY: &'static [u8] = include_bytes!("fixtures/client.key");
+
+fn pem_identity() -> ClientIdentity {
+    ClientIdentity::Pem{ certificate_chain: CLIENT_PEM.to_vec(), private_key: CLIENT_KEY.to_vec() }
+}
+
+/**
+* Test that a PEM bundle is split into its certificates
+*
+*/
+#[test]
+fn test_pem_bundle() {
+    let mut bundle = CA_PEM.to_vec();
+    bundle.extend_from_slice(b"\n");
+    bundle.extend_from_slice(SERVER_PEM);
+
+    let certificates = pem_certificates(&bundle);
+    assert_eq!(certificates.len(), 2);
+    assert_eq!(certificates[1], String::from_utf8_lossy(SERVER_PEM).trim().as_bytes().to_vec());
+
+    assert!(pem_certificates(b"not a certificate").is_empty());
+}
+
+/**
+* Test that TLS settings the backend can't use are reported by the builder
+*
+*/
+#[test]
+fn test_invalid_tls_settings() {
+
+    let err = ApiAIClient::builder()
+        .access_token(TOKEN)
+        .tls_config(TlsConfig{
+            root_certificates: vec![Certificate::Pem(b"not a certificate".to_vec())],
+            ..Default::default()
+        })
+        .build()
+        .err()
+        .unwrap();
+
+    match err.problems[0] {
+        ConfigProblem::InvalidTls(_) => (),
+        ref other => panic!("Expected InvalidTls, got {:?}", other)
+    }
+}
+
+/**
+* Test that native-tls refuses PEM identities, which it has no way to load
+*
+*/
+#[cfg(not(feature = "rustls"))]
+#[test]
+fn test_native_rejects_pem_identity() {
+
+    let err = ApiAIClient::builder()
+        .access_token(TOKEN)
+        .tls_config(TlsConfig{ identity: Option::Some(pem_identity()), ..Default::default() })
+        .build()
+        .err()
+        .unwrap();
+
+    assert_eq!(err.problems.len(), 1);
+    assert!(err.to_string().contains("PEM client identities"));
+}
+
+/**
+* native-tls can't ask for a client certificate on the server side, so these tests check that a
+* PKCS#12 identity is loaded and a connection made with it, but not that the server receives it.
+* Presenting the identity is covered against a server that requires it by the rustls tests.
+*/
+#[cfg(not(feature = "rustls"))]
+mod native_backend {
+
+    use std::io::Write;
+    use std::net::TcpListener;
+    use std::rc::Rc;
+    use std::thread::{self, JoinHandle};
+
+    use hyper_native_tls::native_tls::{Pkcs12, TlsAcceptor};
+    use tokio_core::reactor::Core;
+
+    use async_client::{AsyncApiAIClient, HyperAsyncTransport};
+    use builder::ConfigProblem;
+    use client::ApiAIClient;
+    use tls::{Certificate, ClientIdentity, TlsConfig};
+    use transport::{PoolConfig, ProxyMode};
+    use test::mock_server::{json_response, read_request};
+    use test::fixtures::{GREETING_RESPONSE, hello};
+    use super::{CA_PEM, TOKEN};
+
+    static SERVER_P12: &'static [u8] = include_bytes!("fixtures/server.p12");
+    static CLIENT_P12: &'static [u8] = include_bytes!("fixtures/client.p12");
+    static P12_PASSWORD: &'static str = "apiai";
+
+    fn pkcs12_identity() -> ClientIdentity {
+        ClientIdentity::Pkcs12{ der: CLIENT_P12.to_vec(), password: String::from(P12_PASSWORD) }
+    }
+
+    /**
+    * Serve a single HTTPS request for `localhost` with a certificate signed by the test CA
+    */
+    fn start_tls_server() -> (String, JoinHandle<bool>) {
+
+        let identity = Pkcs12::from_der(SERVER_P12, P12_PASSWORD).unwrap();
+        let acceptor = TlsAcceptor::builder(identity).unwrap().build().unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("https://localhost:{}", listener.local_addr().unwrap().port());
+
+        let handle = thread::spawn(move || {
+            let (socket, _) = listener.accept().unwrap();
+            let mut stream = match acceptor.accept(socket) {
+                Result::Ok(stream) => stream,
+                Result::Err(_) => return false
+            };
+
+            let request = read_request(&mut stream);
+            if request.is_empty() {
+                return false;
+            }
+            stream.write_all(json_response(200, GREETING_RESPONSE).as_bytes()).unwrap();
+            let _ = stream.shutdown();
+            true
+        });
+
+        (url, handle)
+    }
+
+    /**
+    * Test that a server signed by a private CA is trusted once the CA is added
+    *
+    */
+    #[test]
+    fn test_private_ca() {
+
+        let (url, server) = start_tls_server();
+
+        let client = ApiAIClient::builder()
+            .access_token(TOKEN)
+            .base_url(url)
+            .without_proxy()
+            .tls_config(TlsConfig{
+                root_certificates: vec![Certificate::Pem(CA_PEM.to_vec())],
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+
+        let response = client.query(hello()).unwrap();
+        assert_eq!(response.result.fulfillment.speech, "Hi there!");
+        assert!(server.join().unwrap());
+    }
+
+    /**
+    * Test that the private CA isn't trusted by default
+    *
+    */
+    #[test]
+    fn test_untrusted_ca() {
+
+        let (url, server) = start_tls_server();
+
+        let client = ApiAIClient::builder()
+            .access_token(TOKEN)
+            .base_url(url)
+            .without_proxy()
+            .build()
+            .unwrap();
+
+        assert!(client.query(hello()).is_err());
+        assert!(!server.join().unwrap());
+    }
+
+    /**
+    * Test that changing the pool settings after the TLS settings keeps the private CA
+    *
+    */
+    #[test]
+    fn test_setters_keep_tls_config() {
+
+        let (url, server) = start_tls_server();
+
+        let mut client = ApiAIClient::builder()
+            .access_token(TOKEN)
+            .base_url(url)
+            .without_proxy()
+            .build()
+            .unwrap();
+
+        client.set_tls_config(TlsConfig{
+            root_certificates: vec![Certificate::Pem(CA_PEM.to_vec())],
+            ..Default::default()
+        });
+        client.set_pool_config(PoolConfig{ max_idle: 1, ..Default::default() });
+        assert_eq!(client.transport_settings.proxy, ProxyMode::Direct);
+
+        client.query(hello()).unwrap();
+        assert!(server.join().unwrap());
+    }
+
+    /**
+    * Test that a client with a PKCS#12 identity connects
+    *
+    */
+    #[test]
+    fn test_client_identity() {
+
+        let (url, server) = start_tls_server();
+
+        let client = ApiAIClient::builder()
+            .access_token(TOKEN)
+            .base_url(url)
+            .without_proxy()
+            .tls_config(TlsConfig{
+                root_certificates: vec![Certificate::Pem(CA_PEM.to_vec())],
+                identity: Option::Some(pkcs12_identity()),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+
+        client.query(hello()).unwrap();
+        assert!(server.join().unwrap());
+    }
+
+    /**
+    * Test that a PKCS#12 identity that can't be opened is reported by the builder
+    *
+    */
+    #[test]
+    fn test_wrong_pkcs12_password() {
+
+        let err = ApiAIClient::builder()
+            .access_token(TOKEN)
+            .tls_config(TlsConfig{
+                identity: Option::Some(ClientIdentity::Pkcs12{ der: CLIENT_P12.to_vec(), password: String::from("wrong") }),
+                ..Default::default()
+            })
+            .build()
+            .err()
+            .unwrap();
+
+        match err.problems[0] {
+            ConfigProblem::InvalidTls(_) => (),
+            ref other => panic!("Expected InvalidTls, got {:?}", other)
+        }
+    }
+
+    /**
+    * Test that the async transport negotiates TLS with the same settings
+    *
+    */
+    #[test]
+    fn test_async_private_ca() {
+
+        let (url, server) = start_tls_server();
+
+        let mut core = Core::new().unwrap();
+        let tls = TlsConfig{
+            root_certificates: vec![Certificate::Pem(CA_PEM.to_vec())],
+            identity: Option::Some(pkcs12_identity()),
+            ..Default::default()
+        };
+
+        let mut client = AsyncApiAIClient::new(&core.handle(), String::from(TOKEN)).unwrap();
+        client.transport = Rc::new(HyperAsyncTransport::with_tls_config(&core.handle(), &tls).unwrap());
+        client.base_url = url;
+
+        let response = core.run(client.query(hello())).unwrap();
+        assert_eq!(response.result.fulfillment.speech, "Hi there!");
+        assert!(server.join().unwrap());
+    }
+}
+
+#[cfg(feature = "rustls")]
+mod rustls_backend {
+
+    use std::io::Write;
+    use std::net::TcpListener;
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use std::thread::{self, JoinHandle};
+
+    use tokio_core::reactor::Core;
+    use tokio_rustls::rustls::{AllowAnyAuthenticatedClient, NoClientAuth, RootCertStore,
+                               ServerConfig, ServerSession, Session, StreamOwned};
+    use tokio_rustls::rustls::internal::pemfile;
+
+    use async_client::{AsyncApiAIClient, HyperAsyncTransport};
+    use client::ApiAIClient;
+    use tls::{Certificate, ClientIdentity, TlsConfig};
+    use transport::{PoolConfig, ProxyMode};
+    use test::mock_server::{json_response, read_request};
+    use test::fixtures::{GREETING_RESPONSE, hello};
+    use super::{CA_PEM, SERVER_PEM, TOKEN, pem_identity};
+
+    static SERVER_KEY: &'static [u8] = include_bytes!("fixtures/server.key");
+
+    /**
+    * Serve a single HTTPS request for `localhost` with a certificate signed by the test CA,
+    * optionally requiring a client certificate signed by it too.
+    */
+    fn start_tls_server(require_client_cert: bool) -> (String, JoinHandle<bool>) {
+
+        let verifier = if require_client_cert {
+            let mut roots = RootCertStore::empty();
+            roots.add_pem_file(&mut &CA_PEM[..]).unwrap();
+            AllowAnyAuthenticatedClient::new(roots)
+        } else {
+            NoClientAuth::new()
+        };
+
+        let mut config = ServerConfig::new(verifier);
+        let chain = pemfile::certs(&mut &SERVER_PEM[..]).unwrap();
+        let key = pemfile::pkcs8_private_keys(&mut &SERVER_KEY[..]).unwrap().remove(0);
+        config.set_single_cert(chain, key).unwrap();
+        let config = Arc::new(config);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("https://localhost:{}", listener.local_addr().unwrap().port());
+
+        let handle = thread::spawn(move || {
+            let (socket, _) = listener.accept().unwrap();
+            let mut stream = StreamOwned::new(ServerSession::new(&config), socket);
+
+            let request = read_request(&mut stream);
+            if request.is_empty() {
+                return false;
+            }
+            stream.write_all(json_response(200, GREETING_RESPONSE).as_bytes()).unwrap();
+            stream.sess.send_close_notify();
+            let _ = stream.flush();
+            true
+        });
+
+        (url, handle)
+    }
+
+    /**
+    * Test that a server signed by a private CA is trusted once the CA is added
+    *
+    */
+    #[test]
+    fn test_private_ca() {
+
+        let (url, server) = start_tls_server(false);
+
+        let client = ApiAIClient::builder()
+            .access_token(TOKEN)
+            .base_url(url)
+            .without_proxy()
+            .tls_config(TlsConfig{
+                root_certificates: vec![Certificate::Pem(CA_PEM.to_vec())],
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+
+        let response = client.query(hello()).unwrap();
+        assert_eq!(response.result.fulfillment.speech, "Hi there!");
+        assert!(server.join().unwrap());
+    }
+
+    /**
+    * Test that the private CA isn't trusted by default
+    *
+    */
+    #[test]
+    fn test_untrusted_ca() {
+
+        let (url, _server) = start_tls_server(false);
+
+        let client = ApiAIClient::builder()
+            .access_token(TOKEN)
+            .base_url(url)
+            .without_proxy()
+            .build()
+            .unwrap();
+
+        assert!(client.query(hello()).is_err());
+    }
+
+    /**
+    * Test presenting a client certificate to a server that requires one
+    *
+    */
+    #[test]
+    fn test_client_identity() {
+
+        let (url, server) = start_tls_server(true);
+
+        let client = ApiAIClient::builder()
+            .access_token(TOKEN)
+            .base_url(url)
+            .without_proxy()
+            .tls_config(TlsConfig{
+                root_certificates: vec![Certificate::Pem(CA_PEM.to_vec())],
+                identity: Option::Some(pem_identity()),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+
+        client.query(hello()).unwrap();
+        assert!(server.join().unwrap());
+    }
+
+    /**
+    * Test that changing the pool settings after the TLS settings keeps the private CA
+    *
+    */
+    #[test]
+    fn test_setters_keep_tls_config() {
+
+        let (url, server) = start_tls_server(false);
+
+        let mut client = ApiAIClient::builder()
+            .access_token(TOKEN)
+            .base_url(url)
+            .without_proxy()
+            .build()
+            .unwrap();
+
+        client.set_tls_config(TlsConfig{
+            root_certificates: vec![Certificate::Pem(CA_PEM.to_vec())],
+            ..Default::default()
+        });
+        client.set_pool_config(PoolConfig{ max_idle: 1, ..Default::default() });
+        assert_eq!(client.transport_settings.proxy, ProxyMode::Direct);
+
+        client.query(hello()).unwrap();
+        assert!(server.join().unwrap());
+    }
+
+    /**
+    * Test that rustls refuses PKCS#12 identities
+    *
+    */
+    #[test]
+    fn test_rejects_pkcs12_identity() {
+
+        let err = ApiAIClient::builder()
+            .access_token(TOKEN)
+            .tls_config(TlsConfig{
+                identity: Option::Some(ClientIdentity::Pkcs12{ der: Vec::new(), password: String::new() }),
+                ..Default::default()
+            })
+            .build()
+            .err()
+            .unwrap();
+
+        assert!(err.to_string().contains("PKCS#12"));
+    }
+
+    /**
+    * Test that the async transport negotiates TLS with the same settings
+    *
+    */
+    #[test]
+    fn test_async_private_ca() {
+
+        let (url, server) = start_tls_server(false);
+
+        let mut core = Core::new().unwrap();
+        let tls = TlsConfig{
+            root_certificates: vec![Certificate::Pem(CA_PEM.to_vec())],
+            ..Default::default()
+        };
+
+        let mut client = AsyncApiAIClient::new(&core.handle(), String::from(TOKEN)).unwrap();
+        client.transport = Rc::new(HyperAsyncTransport::with_tls_config(&core.handle(), &tls).unwrap());
+        client.base_url = url;
+
+        let response = core.run(client.query(hello())).unwrap();
+        assert_eq!(response.result.fulfillment.speech, "Hi there!");
+        assert!(server.join().unwrap());
+    }
+}
@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use client::{ApiAIClient,ApiRequest};
+use transport::{HttpMethod, MemoryTransport};
+use test::fixtures::GREETING_RESPONSE;
+
+use serde_json;
+
+/**
+* Test that query builds the expected request and hands it to the transport
+*
+*/
+#[test]
+fn test_query_sends_request_through_transport() {
+
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(200, GREETING_RESPONSE);
+
+    let client = ApiAIClient{
+        access_token: String::from("ce2f54f8eb444d74af85f89e30ef2fd3"),
+        transport: transport.clone(),
+        ..Default::default()
+    };
+
+    let req = ApiRequest{
+        query: Option::Some(String::from("Hello!")),
+        session_id: String::from("12345"),
+        ..Default::default()
+    };
+
+    let response = client.query(req).unwrap();
+    assert_eq!(response.result.action, String::from("smalltalk.greetings"));
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+
+    let sent = &requests[0];
+    assert_eq!(sent.method, HttpMethod::Post);
+    assert_eq!(sent.url, "https://api.api.ai/v1/query?v=20150910");
+    assert!(sent.headers.contains(&(String::from("Authorization"),
+                                    String::from("Bearer ce2f54f8eb444d74af85f89e30ef2fd3"))));
+
+    let body : ApiRequest = serde_json::from_slice(&sent.body).unwrap();
+    assert_eq!(body.query.unwrap(), "Hello!");
+    assert_eq!(body.session_id, "12345");
+}
+
+/**
+* Test that a failure in the transport is passed back to the caller
+*
+*/
+#[test]
+fn test_query_transport_failure() {
+
+    let client = ApiAIClient{
+        transport: Arc::new(MemoryTransport::new()),
+        ..Default::default()
+    };
+
+    let req = ApiRequest{
+        query: Option::Some(String::from("Hello!")),
+        ..Default::default()
+    };
+
+    assert!(client.query(req).is_err());
+}
+
+/**
+* Test that clones of a client share one transport and can be used from several threads
+*
+*/
+#[test]
+fn test_client_shared_between_threads() {
+    use std::thread;
+
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(200, GREETING_RESPONSE);
+    transport.push_json(200, GREETING_RESPONSE);
+
+    let client = ApiAIClient{
+        transport: transport.clone(),
+        ..Default::default()
+    };
+
+    let handles : Vec<_> = (0..2).map(|_| {
+        let client = client.clone();
+        thread::spawn(move || {
+            let req = ApiRequest{
+                query: Option::Some(String::from("Hello!")),
+                ..Default::default()
+            };
+            client.query(req).unwrap().result.action
+        })
+    }).collect();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), "smalltalk.greetings");
+    }
+
+    assert_eq!(transport.requests().len(), 2);
+}
+
+/**
+* Test that HyperTransport talks HTTP to a real socket
+*
+*/
+#[test]
+fn test_hyper_transport_over_http() {
+    use test::mock_server::{MockServer, json_response};
+
+    let server = MockServer::start(vec![json_response(200, GREETING_RESPONSE)]);
+
+    let client = ApiAIClient{
+        access_token: String::from("ce2f54f8eb444d74af85f89e30ef2fd3"),
+        base_url: server.url.clone(),
+        ..Default::default()
+    };
+
+    let req = ApiRequest{
+        query: Option::Some(String::from("Hello!")),
+        ..Default::default()
+    };
+
+    let response = client.query(req).unwrap();
+    assert_eq!(response.result.fulfillment.speech, "Hi there!");
+
+    let requests = server.requests();
+    assert!(requests[0].starts_with("POST /query?v=20150910 HTTP/1.1"));
+}
@@ -0,0 +1,76 @@
+use client::{ApiRequest, EntityEntry, UserEntity};
+use transport::HttpMethod;
+use test::fixtures::{SUCCESS, TOKEN, client_with_transport};
+
+use serde_json;
+
+fn saved_addresses() -> UserEntity {
+    UserEntity{
+        session_id: String::new(),
+        name: String::from("address"),
+        extend: false,
+        entries: vec![EntityEntry{
+            value: String::from("10 Downing Street"),
+            synonyms: vec![String::from("home"), String::from("my house")],
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+/**
+* Test creating, fetching, updating and deleting user entities for a session
+*
+*/
+#[test]
+fn test_user_entities() {
+
+    let (client, transport) = client_with_transport(TOKEN);
+    transport.push_json(200, SUCCESS);
+    transport.push_json(200, r#"{"sessionId":"12345","name":"address","extend":false,"entries":[{"value":"10 Downing Street","synonyms":["home","my house"]}]}"#);
+    transport.push_json(200, SUCCESS);
+    transport.push_json(200, SUCCESS);
+
+    client.create_user_entities("12345", &[saved_addresses()]).unwrap();
+
+    let entity = client.user_entity("12345", "address").unwrap();
+    assert_eq!(entity.session_id, "12345");
+    assert_eq!(entity.entries[0].synonyms[0], "home");
+
+    let mut extended = saved_addresses();
+    extended.extend = true;
+    client.update_user_entity("12345", "address", &extended).unwrap();
+    client.delete_user_entity("12345", "address").unwrap();
+
+    let requests = transport.requests();
+    let methods: Vec<HttpMethod> = requests.iter().map(|request| request.method.clone()).collect();
+    assert_eq!(methods, vec![HttpMethod::Post, HttpMethod::Get, HttpMethod::Put, HttpMethod::Delete]);
+
+    assert_eq!(requests[0].url, "https://api.api.ai/v1/userEntities?sessionId=12345&v=20150910");
+    let created : serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(created, serde_json::from_str::<serde_json::Value>(r#"[{"sessionId":"12345","name":"address","extend":false,"entries":[{"value":"10 Downing Street","synonyms":["home","my house"]}]}]"#).unwrap());
+
+    assert_eq!(requests[2].url, "https://api.api.ai/v1/userEntities/address?sessionId=12345&v=20150910");
+    let updated : serde_json::Value = serde_json::from_slice(&requests[2].body).unwrap();
+    assert_eq!(updated["extend"].as_bool(), Option::Some(true));
+    assert_eq!(updated["sessionId"].as_str(), Option::Some("12345"));
+}
+
+/**
+* Test that user entities can be sent inline with a query
+*
+*/
+#[test]
+fn test_inline_user_entities() {
+
+    let req = ApiRequest{
+        query: Option::Some(String::from("take me home")),
+        session_id: String::from("12345"),
+        entities: vec![saved_addresses()],
+        ..Default::default()
+    };
+
+    let query_string = r#"{"query":"take me home","sessionId":"12345","lang":"en","contexts":[],"entities":[{"name":"address","extend":false,"entries":[{"value":"10 Downing Street","synonyms":["home","my house"]}]}]}"#;
+
+    assert_eq!(query_string, serde_json::to_string(&req).unwrap());
+}
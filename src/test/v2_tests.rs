@@ -0,0 +1,239 @@
+use std::sync::Arc;
+
+use client::{ApiContext, ApiError, ApiEvent, ApiLocation, ApiMessage, ApiRequest, EntityEntry, Parameters, UserEntity};
+use transport::{HttpMethod, MemoryTransport};
+use v2::{DetectIntentRequest, DialogflowClient, QueryInput, SessionPath};
+
+use serde_json::{self, Value};
+
+static DETECT_INTENT_RESPONSE: &'static str = r#"{
+  "responseId": "3c3b9b84-2d1c-4c3e-9a4b-8f0e6d2a1b57",
+  "queryResult": {
+    "queryText": "weather in London tomorrow",
+    "action": "weather.forecast",
+    "parameters": {"city": "London", "days": 1, "date": ""},
+    "allRequiredParamsPresent": true,
+    "fulfillmentText": "Looking up the weather in London",
+    "fulfillmentMessages": [
+      {"text": {"text": ["Looking up the weather in London"]}},
+      {"image": {"imageUri": "https://example.com/cloud.png"}},
+      {"quickReplies": {"quickReplies": ["Today", "Tomorrow"]}},
+      {"card": {"title": "London", "imageUri": "https://example.com/london.png", "buttons": [{"text": "Forecast", "postback": "https://example.com/london"}]}, "platform": "FACEBOOK"},
+      {"payload": {"google": {"expectUserResponse": false}}, "platform": "ACTIONS_ON_GOOGLE"},
+      {"simpleResponses": {"simpleResponses": [{"textToSpeech": "Looking up the weather in London"}]}, "platform": "ACTIONS_ON_GOOGLE"}
+    ],
+    "outputContexts": [
+      {"name": "projects/weather-bot/agent/sessions/12345/contexts/weather", "lifespanCount": 5, "parameters": {"city": "London", "days": 1}}
+    ],
+    "intent": {"name": "projects/weather-bot/agent/intents/2a7c1d04-3b5e-4f8a-9d61-7e0b8c2f4a15", "displayName": "weather.forecast"},
+    "intentDetectionConfidence": 0.87,
+    "languageCode": "en"
+  }
+}"#;
+
+fn client_with_transport() -> (DialogflowClient, Arc<MemoryTransport>) {
+    let transport = Arc::new(MemoryTransport::new());
+
+    let client = DialogflowClient{
+        transport: transport.clone(),
+        ..DialogflowClient::new("weather-bot", "ya29.service-account-token")
+    };
+
+    (client, transport)
+}
+
+/**
+* Test building and parsing session paths
+*
+*/
+#[test]
+fn test_session_paths() {
+
+    let session = SessionPath::new("weather-bot", "12345");
+    assert_eq!(session.to_string(), "projects/weather-bot/agent/sessions/12345");
+    assert_eq!(session.context_name("weather"), "projects/weather-bot/agent/sessions/12345/contexts/weather");
+
+    assert_eq!(SessionPath::parse("projects/weather-bot/agent/sessions/12345"), Option::Some(session));
+    assert_eq!(SessionPath::parse("projects/weather-bot/agent/sessions/"), Option::None);
+    assert_eq!(SessionPath::parse("projects/weather-bot/agent/intents/12345"), Option::None);
+}
+
+/**
+* Test that a v1 query is sent as a detectIntent request and the reply converted back
+*
+*/
+#[test]
+fn test_query_through_detect_intent() {
+
+    let (client, transport) = client_with_transport();
+    transport.push_json(200, DETECT_INTENT_RESPONSE);
+
+    let mut parameters = Parameters::new();
+    parameters.insert("city", "Paris");
+
+    let req = ApiRequest{
+        query: Option::Some(String::from("weather in London tomorrow")),
+        session_id: String::from("12345"),
+        contexts: vec![ApiContext{ name: String::from("weather"), parameters: parameters, lifespan: Option::Some(2), ..Default::default() }],
+        entities: vec![UserEntity{
+            session_id: String::new(),
+            name: String::from("city"),
+            extend: true,
+            entries: vec![EntityEntry{ value: String::from("London"), synonyms: vec![String::from("ldn")], ..Default::default() }],
+            ..Default::default()
+        }],
+        timezone: Option::Some(String::from("Europe/London")),
+        location: Option::Some(ApiLocation{ latitude: 51.5074, longitude: -0.1278 }),
+        ..Default::default()
+    };
+
+    let response = client.query(req).unwrap();
+
+    let sent = &transport.requests()[0];
+    assert_eq!(sent.method, HttpMethod::Post);
+    assert_eq!(sent.url, "https://dialogflow.googleapis.com/v2/projects/weather-bot/agent/sessions/12345:detectIntent");
+    assert!(sent.headers.contains(&(String::from("Authorization"), String::from("Bearer ya29.service-account-token"))));
+
+    let body: Value = serde_json::from_slice(&sent.body).unwrap();
+    let expected: Value = serde_json::from_str(r#"{
+      "queryInput": {"text": {"text": "weather in London tomorrow", "languageCode": "en"}},
+      "queryParams": {
+        "timeZone": "Europe/London",
+        "geoLocation": {"latitude": 51.5074, "longitude": -0.1278},
+        "contexts": [{"name": "projects/weather-bot/agent/sessions/12345/contexts/weather", "lifespanCount": 2, "parameters": {"city": "Paris"}}],
+        "sessionEntityTypes": [{"name": "projects/weather-bot/agent/sessions/12345/entityTypes/city", "entityOverrideMode": "ENTITY_OVERRIDE_MODE_SUPPLEMENT", "entities": [{"value": "London", "synonyms": ["ldn"]}]}]
+      }
+    }"#).unwrap();
+    assert_eq!(body, expected);
+
+    assert_eq!(response.id, "3c3b9b84-2d1c-4c3e-9a4b-8f0e6d2a1b57");
+    assert_eq!(response.session_id, "12345");
+    assert_eq!(response.status.code, 200);
+    assert_eq!(response.result.resolved_query, "weather in London tomorrow");
+    assert_eq!(response.result.action, "weather.forecast");
+    assert!(!response.result.action_incomplete);
+    assert_eq!(response.result.parameters.get_str("city"), Option::Some("London"));
+    assert_eq!(response.result.parameters.get_number("days"), Option::Some(1.0));
+    assert_eq!(response.result.contexts[0].name, "weather");
+    assert_eq!(response.result.contexts[0].lifespan, Option::Some(5));
+    assert_eq!(response.result.metadata.intent_id.as_ref().unwrap(), "2a7c1d04-3b5e-4f8a-9d61-7e0b8c2f4a15");
+    assert_eq!(response.result.metadata.intent_name.as_ref().unwrap(), "weather.forecast");
+    assert_eq!(response.result.metadata.webhook_used, "false");
+    assert_eq!(response.result.fulfillment.speech, "Looking up the weather in London");
+    assert_eq!(response.result.score, 0.87);
+
+    let messages = response.result.fulfillment.messages.unwrap();
+    assert_eq!(messages.len(), 5);
+    match messages[1] {
+        ApiMessage::Image(ref image) => assert_eq!(image.image_url, "https://example.com/cloud.png"),
+        _ => panic!("expected an image message")
+    }
+    match messages[2] {
+        ApiMessage::QuickReplies(ref quick_replies) => {
+            assert!(quick_replies.title.is_none());
+            assert_eq!(quick_replies.replies, vec![String::from("Today"), String::from("Tomorrow")]);
+        },
+        _ => panic!("expected a quick replies message")
+    }
+    match messages[3] {
+        ApiMessage::Card(ref card) => {
+            assert_eq!(card.title, "London");
+            assert!(card.subtitle.is_none());
+            assert_eq!(card.image_url.as_ref().unwrap(), "https://example.com/london.png");
+            assert_eq!(card.buttons[0].postback.as_ref().unwrap(), "https://example.com/london");
+        },
+        _ => panic!("expected a card message")
+    }
+    assert_eq!(messages[3].platform(), Option::Some("facebook"));
+    match messages[4] {
+        ApiMessage::CustomPayload(ref payload) => assert_eq!(payload.payload["google"]["expectUserResponse"].as_bool(), Option::Some(false)),
+        _ => panic!("expected a custom payload message")
+    }
+    assert_eq!(messages[4].platform(), Option::Some("google"));
+}
+
+/**
+* Test converting an event query, and a reply whose webhook call failed
+*
+*/
+#[test]
+fn test_event_and_webhook_failure() {
+
+    let mut data = Parameters::new();
+    data.insert("name", "Sam");
+
+    let req = ApiRequest{
+        event: Option::Some(ApiEvent{ name: String::from("WELCOME"), data: Option::Some(data) }),
+        session_id: String::from("12345"),
+        ..Default::default()
+    };
+
+    let request = DetectIntentRequest::from_api_request(&req, &SessionPath::new("weather-bot", "12345")).unwrap();
+    assert!(request.query_params.is_none());
+    assert!(request.query_input.text.is_none());
+    let event = request.query_input.event.unwrap();
+    assert_eq!(event.name, "WELCOME");
+    assert_eq!(event.parameters.get_str("name"), Option::Some("Sam"));
+
+    let (client, transport) = client_with_transport();
+    transport.push_json(200, r#"{"responseId":"8d1f","queryResult":{"queryText":"WELCOME","allRequiredParamsPresent":false,"languageCode":"en"},"webhookStatus":{"code":4,"message":"Webhook call failed. Error: DEADLINE_EXCEEDED."}}"#);
+
+    let response = client.detect_intent(&client.session("12345"), &DetectIntentRequest{
+        query_params: Option::None,
+        query_input: QueryInput::event("WELCOME", "en"),
+    }).unwrap().into_api_response("12345");
+
+    assert_eq!(response.status.code, 206);
+    assert_eq!(response.status.error_type, "partial_content");
+    assert_eq!(response.result.metadata.webhook_used, "true");
+    assert!(response.result.action_incomplete);
+    assert!(response.result.fulfillment.messages.is_none());
+}
+
+/**
+* Test that Google API errors are mapped onto ApiError with their status
+*
+*/
+#[test]
+fn test_detect_intent_errors() {
+
+    let (client, transport) = client_with_transport();
+    transport.push_json(403, r#"{"error":{"code":403,"message":"IAM permission 'dialogflow.sessions.detectIntent' on 'projects/weather-bot/agent' denied.","status":"PERMISSION_DENIED"}}"#);
+    transport.push_json(429, r#"{"error":{"code":429,"message":"Quota exceeded","status":"RESOURCE_EXHAUSTED"}}"#);
+
+    match client.query(ApiRequest{ query: Option::Some(String::from("hi")), ..Default::default() }) {
+        Result::Err(ApiError::Unauthorized(details)) => {
+            assert_eq!(details.code, 403);
+            assert_eq!(details.error_type, "PERMISSION_DENIED");
+            assert!(details.error_details.unwrap().contains("dialogflow.sessions.detectIntent"));
+        },
+        _ => panic!("expected Unauthorized")
+    }
+
+    match client.query(ApiRequest{ query: Option::Some(String::from("hi")), ..Default::default() }) {
+        Result::Err(ApiError::RateLimited(details)) => assert_eq!(details.error_type, "RESOURCE_EXHAUSTED"),
+        _ => panic!("expected RateLimited")
+    }
+}
+
+/**
+* Test that a query that can't be expressed as a detectIntent call fails without being sent
+*
+*/
+#[test]
+fn test_invalid_query() {
+
+    let (client, transport) = client_with_transport();
+
+    match client.query(ApiRequest{ session_id: String::from("12345"), ..Default::default() }) {
+        Result::Err(ApiError::InvalidRequest(msg)) => assert!(msg.contains("query or an event")),
+        _ => panic!("expected InvalidRequest")
+    }
+
+    match client.query(ApiRequest{ query: Option::Some(String::from("hi")), session_id: String::new(), ..Default::default() }) {
+        Result::Err(ApiError::InvalidRequest(msg)) => assert!(msg.contains("session id")),
+        _ => panic!("expected InvalidRequest")
+    }
+
+    assert!(transport.requests().is_empty());
+}
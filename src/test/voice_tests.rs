@@ -0,0 +1,91 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::{Cursor, Write};
+use std::sync::Arc;
+
+use client::{ApiAIClient,ApiRequest};
+use transport::{HyperTransport, MemoryTransport};
+use voice::{AudioSource, AudioContentType};
+use test::mock_server::{MockServer, json_response};
+
+static VOICE_RESPONSE: &'static str = r#"{"id":"b340a1f7-abee-4e13-9bdd-5e8938a48b7d","timestamp":"2017-02-09T15:38:26.548Z","lang":"en","result":{"source":"domains","resolvedQuery":"hello there","action":"smalltalk.greetings","actionIncomplete":false,"parameters":{},"contexts":[],"metadata":{},"fulfillment":{"speech":"Hi there!"},"score":1},"status":{"code":200,"errorType":"success"},"sessionId":"12345"}"#;
+
+static AUDIO: &'static [u8] = b"RIFF\x24\x00\x00\x00WAVEfmt \x10\x00\x00\x00\x01\x00\x01\x00\x80\x3e\x00\x00";
+
+fn voice_request() -> ApiRequest {
+    ApiRequest{
+        session_id: String::from("12345"),
+        ..Default::default()
+    }
+}
+
+/**
+* Test that the request and audio are sent as a multipart form
+*
+*/
+#[test]
+fn test_query_voice_multipart() {
+
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(200, VOICE_RESPONSE);
+
+    let client = ApiAIClient{
+        access_token: String::from("ce2f54f8eb444d74af85f89e30ef2fd3"),
+        transport: transport.clone(),
+        ..Default::default()
+    };
+
+    let response = client.query_voice(voice_request(), AudioSource::from_reader(Cursor::new(AUDIO)), AudioContentType::Wav).unwrap();
+    assert_eq!(response.result.resolved_query, "hello there");
+
+    let request = &transport.requests()[0];
+    assert_eq!(request.url, "https://api.api.ai/v1/query?v=20150910");
+
+    let content_type = &request.headers.iter().find(|&&(ref name, _)| name == "Content-Type").unwrap().1;
+    assert!(content_type.starts_with("multipart/form-data; boundary="));
+    let boundary = &content_type["multipart/form-data; boundary=".len()..];
+
+    let body = String::from_utf8_lossy(&request.body).into_owned();
+    let parts: Vec<&str> = body.split(&format!("--{}", boundary)).collect();
+    assert_eq!(parts.len(), 4);
+    assert_eq!(parts[0], "");
+    assert!(parts[1].contains("Content-Disposition: form-data; name=\"request\"\r\nContent-Type: application/json\r\n\r\n{"));
+    assert!(parts[1].contains(r#""sessionId":"12345""#));
+    assert!(parts[2].contains("name=\"voiceData\"; filename=\"voice.wav\"\r\nContent-Type: audio/wav\r\n\r\n"));
+    assert!(request.body.windows(AUDIO.len()).any(|window| window == AUDIO));
+    assert_eq!(parts[3], "--\r\n");
+}
+
+/**
+* Test streaming audio from a file (with a length) and from a reader (chunked) over HTTP
+*
+*/
+#[test]
+fn test_query_voice_over_http() {
+
+    let server = MockServer::start(vec![json_response(200, VOICE_RESPONSE), json_response(200, VOICE_RESPONSE)]);
+
+    let mut client = ApiAIClient{
+        base_url: server.url.clone(),
+        transport: Arc::new(HyperTransport::with_settings(Default::default(), Option::None, Default::default())),
+        ..Default::default()
+    };
+    client.access_token = String::from("ce2f54f8eb444d74af85f89e30ef2fd3");
+
+    let path = env::temp_dir().join(format!("apiai-voice-test-{}.raw", ::std::process::id()));
+    File::create(&path).unwrap().write_all(AUDIO).unwrap();
+
+    let audio = AudioSource::from_file(&path).unwrap();
+    let response = client.query_voice(voice_request(), audio, AudioContentType::Pcm16k).unwrap();
+    assert_eq!(response.result.resolved_query, "hello there");
+    fs::remove_file(&path).unwrap();
+
+    let audio = AudioSource::from_reader(Cursor::new(AUDIO));
+    client.query_voice(voice_request(), audio, AudioContentType::Wav).unwrap();
+
+    let requests = server.requests();
+    assert!(requests[0].to_lowercase().contains("content-length:"));
+    assert!(requests[0].contains("Content-Type: audio/L16; rate=16000; channels=1\r\n"));
+    assert!(requests[0].contains("Bearer ce2f54f8eb444d74af85f89e30ef2fd3"));
+    assert!(requests[1].to_lowercase().contains("transfer-encoding: chunked"));
+}
@@ -0,0 +1,197 @@
+#[cfg(all(not(feature = "native-tls"), not(feature = "rustls")))]
+compile_error!("apiai needs a TLS backend: enable either the `native-tls` or the `rustls` feature");
+
+#[cfg(not(feature = "rustls"))]
+mod native_backend;
+#[cfg(not(feature = "rustls"))]
+pub(crate) use self::native_backend::{SslClient, AsyncConnector, ssl_client, async_connector};
+#[cfg(not(feature = "rustls"))]
+use hyper_native_tls::native_tls;
+
+#[cfg(feature = "rustls")]
+mod rustls_backend;
+#[cfg(feature = "rustls")]
+pub(crate) use self::rustls_backend::{SslClient, AsyncConnector, ssl_client, async_connector};
+
+use std::error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* TLS settings used when connecting to the API.
+*
+* By default the system's trusted roots (native-tls) or the Mozilla roots (rustls) are used. Extra
+* `root_certificates` are trusted in addition to those, e.g. a private CA for a gateway that mirrors
+* the api.ai API. `identity` is presented to servers that ask for a client certificate.
+*
+* The backend is picked at compile time: native-tls by default, or rustls with the `rustls` cargo
+* feature. Client identities are backend specific; native-tls takes a PKCS#12 archive while rustls
+* takes PEM encoded certificates and a private key.
+*/
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct TlsConfig {
+    pub root_certificates: Vec<Certificate>,
+    pub identity: Option<ClientIdentity>,
+    /**
+    * Refuse to negotiate anything older than this version
+    */
+    pub min_protocol: Option<TlsVersion>,
+}
+
+/**
+* A certificate in DER form or as PEM text, which may hold several certificates (a CA bundle)
+*
+*/
+#[derive(Clone,Debug,PartialEq)]
+pub enum Certificate {
+    Der(Vec<u8>),
+    Pem(Vec<u8>),
+}
+
+impl Certificate {
+
+    /**
+    * Read a certificate file, treating it as PEM if it contains a PEM header and DER otherwise.
+    */
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Certificate> {
+        let contents = read_file(path)?;
+
+        if String::from_utf8_lossy(&contents).contains("-----BEGIN") {
+            Result::Ok(Certificate::Pem(contents))
+        } else {
+            Result::Ok(Certificate::Der(contents))
+        }
+    }
+}
+
+/**
+* A client certificate and its private key
+*
+*/
+#[derive(Clone,Debug,PartialEq)]
+pub enum ClientIdentity {
+    /**
+    * A PKCS#12 archive and its password, supported by the native-tls backend
+    */
+    Pkcs12{ der: Vec<u8>, password: String },
+    /**
+    * A PEM certificate chain and PEM private key, supported by the rustls backend
+    */
+    Pem{ certificate_chain: Vec<u8>, private_key: Vec<u8> },
+}
+
+impl ClientIdentity {
+
+    pub fn pkcs12_from_file<P: AsRef<Path>>(path: P, password: &str) -> io::Result<ClientIdentity> {
+        Result::Ok(ClientIdentity::Pkcs12{ der: read_file(path)?, password: String::from(password) })
+    }
+
+    pub fn pem_from_files<P: AsRef<Path>, Q: AsRef<Path>>(certificate_chain: P, private_key: Q) -> io::Result<ClientIdentity> {
+        Result::Ok(ClientIdentity::Pem{
+            certificate_chain: read_file(certificate_chain)?,
+            private_key: read_file(private_key)?,
+        })
+    }
+}
+
+/**
+* TLS protocol versions
+*
+*/
+#[derive(Clone,Copy,Debug,PartialEq,PartialOrd)]
+pub enum TlsVersion {
+    Tls10,
+    Tls11,
+    Tls12,
+}
+
+fn read_file<P: AsRef<Path>>(path: P) -> io::Result<Vec<u8>> {
+    let mut contents = Vec::new();
+    File::open(path)?.read_to_end(&mut contents)?;
+    Result::Ok(contents)
+}
+
+/**
+* Split PEM text into its individual certificates
+*/
+pub(crate) fn pem_certificates(pem: &[u8]) -> Vec<Vec<u8>> {
+    let text = String::from_utf8_lossy(pem);
+    let end_marker = "-----END CERTIFICATE-----";
+
+    let mut certificates = Vec::new();
+    let mut rest = &text[..];
+    while let Option::Some(start) = rest.find("-----BEGIN CERTIFICATE-----") {
+        match rest[start..].find(end_marker) {
+            Option::Some(end) => {
+                let end = start + end + end_marker.len();
+                certificates.push(rest[start..end].as_bytes().to_vec());
+                rest = &rest[end..];
+            },
+            Option::None => break
+        }
+    }
+
+    certificates
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* Failure to set up the TLS backend
+*
+*/
+#[derive(Debug)]
+pub enum TlsError {
+    #[cfg(not(feature = "rustls"))]
+    NativeTls(native_tls::Error),
+    InvalidCertificate(String),
+    InvalidIdentity(String),
+    /**
+    * The setting isn't supported by the TLS backend in use
+    */
+    Unsupported(&'static str),
+}
+
+#[cfg(not(feature = "rustls"))]
+impl From<native_tls::Error> for TlsError {
+    fn from(err: native_tls::Error) -> TlsError {
+        TlsError::NativeTls(err)
+    }
+}
+
+impl fmt::Display for TlsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            #[cfg(not(feature = "rustls"))]
+            TlsError::NativeTls(ref err) => write!(f, "{}", err),
+            TlsError::InvalidCertificate(ref msg) => write!(f, "invalid certificate: {}", msg),
+            TlsError::InvalidIdentity(ref msg) => write!(f, "invalid client identity: {}", msg),
+            TlsError::Unsupported(msg) => write!(f, "not supported by this TLS backend: {}", msg),
+        }
+    }
+}
+
+impl error::Error for TlsError {
+    fn description(&self) -> &str {
+        match *self {
+            #[cfg(not(feature = "rustls"))]
+            TlsError::NativeTls(_) => "native-tls error",
+            TlsError::InvalidCertificate(_) => "invalid certificate",
+            TlsError::InvalidIdentity(_) => "invalid client identity",
+            TlsError::Unsupported(_) => "not supported by this TLS backend",
+        }
+    }
+
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            #[cfg(not(feature = "rustls"))]
+            TlsError::NativeTls(ref err) => Option::Some(err),
+            _ => Option::None
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use hyper_async::client::HttpConnector;
+use hyper_native_tls::NativeTlsClient;
+use hyper_native_tls::native_tls::{self, Pkcs12, Protocol, TlsConnector};
+use hyper_tls::HttpsConnector;
+use tokio_core::reactor::Handle;
+
+use super::{Certificate, ClientIdentity, TlsConfig, TlsError, TlsVersion, pem_certificates};
+
+
+pub type SslClient = NativeTlsClient;
+pub type AsyncConnector = HttpsConnector<HttpConnector>;
+
+/**
+* Build a native-tls connector from the settings
+*/
+fn connector(config: &TlsConfig) -> Result<TlsConnector, TlsError> {
+
+    let mut builder = TlsConnector::builder()?;
+
+    for certificate in &config.root_certificates {
+        match *certificate {
+            Certificate::Der(ref der) => {
+                builder.add_root_certificate(native_tls::Certificate::from_der(der)?)?;
+            },
+            Certificate::Pem(ref pem) => {
+                let certificates = pem_certificates(pem);
+                if certificates.is_empty() {
+                    return Result::Err(TlsError::InvalidCertificate(String::from("no certificates found in PEM data")));
+                }
+                for pem in certificates {
+                    builder.add_root_certificate(native_tls::Certificate::from_pem(&pem)?)?;
+                }
+            }
+        }
+    }
+
+    match config.identity {
+        Option::Some(ClientIdentity::Pkcs12{ ref der, ref password }) => {
+            builder.identity(Pkcs12::from_der(der, password)?)?;
+        },
+        Option::Some(ClientIdentity::Pem{ .. }) =>
+            return Result::Err(TlsError::Unsupported("PEM client identities, use a PKCS#12 archive with native-tls")),
+        Option::None => ()
+    }
+
+    if let Option::Some(min_protocol) = config.min_protocol {
+        let protocols: Vec<Protocol> = [(TlsVersion::Tls10, Protocol::Tlsv10),
+                                        (TlsVersion::Tls11, Protocol::Tlsv11),
+                                        (TlsVersion::Tls12, Protocol::Tlsv12)].iter()
+            .filter(|&&(version, _)| version >= min_protocol)
+            .map(|&(_, protocol)| protocol)
+            .collect();
+        builder.supported_protocols(&protocols)?;
+    }
+
+    Result::Ok(builder.build()?)
+}
+
+pub fn ssl_client(config: &TlsConfig) -> Result<SslClient, TlsError> {
+    if *config == TlsConfig::default() {
+        return Result::Ok(NativeTlsClient::new()?);
+    }
+
+    Result::Ok(NativeTlsClient::from(connector(config)?))
+}
+
+pub fn async_connector(config: &TlsConfig, handle: &Handle) -> Result<AsyncConnector, TlsError> {
+    if *config == TlsConfig::default() {
+        return Result::Ok(HttpsConnector::new(4, handle)?);
+    }
+
+    let mut http = HttpConnector::new(4, handle);
+    http.enforce_http(false);
+
+    Result::Ok(HttpsConnector::from((http, connector(config)?)))
+}
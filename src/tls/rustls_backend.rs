@@ -0,0 +1,169 @@
+use futures::{future, Future, Poll};
+use futures::future::Either;
+use hyper_async::Uri;
+use hyper_async::client::{HttpConnector, Service};
+use hyper_rustls::TlsClient;
+use tokio_core::net::TcpStream;
+use tokio_core::reactor::Handle;
+use tokio_io::{AsyncRead, AsyncWrite};
+use tokio_rustls::{ClientConfigExt, TlsStream};
+use tokio_rustls::rustls::{self, ClientConfig, ClientSession, ClientSessionMemoryCache};
+use tokio_rustls::rustls::internal::pemfile;
+use tokio_rustls::webpki::DNSNameRef;
+use webpki_roots;
+
+use super::{Certificate, ClientIdentity, TlsConfig, TlsError};
+
+use std::io::{self, BufReader, Read, Write};
+use std::sync::Arc;
+
+
+pub type SslClient = TlsClient;
+
+/**
+* Build a rustls configuration from the settings.
+*
+* rustls never negotiates anything older than TLS 1.2, so every `min_protocol` is already met.
+*/
+fn client_config(config: &TlsConfig) -> Result<Arc<ClientConfig>, TlsError> {
+
+    let mut tls_config = ClientConfig::new();
+    tls_config.set_persistence(ClientSessionMemoryCache::new(64));
+    tls_config.root_store.add_server_trust_anchors(&webpki_roots::TLS_SERVER_ROOTS);
+
+    for certificate in &config.root_certificates {
+        match *certificate {
+            Certificate::Der(ref der) => {
+                tls_config.root_store.add(&rustls::Certificate(der.clone()))
+                    .map_err(|err| TlsError::InvalidCertificate(format!("{:?}", err)))?;
+            },
+            Certificate::Pem(ref pem) => {
+                match tls_config.root_store.add_pem_file(&mut BufReader::new(&pem[..])) {
+                    Result::Ok((valid, _)) if valid > 0 => (),
+                    _ => return Result::Err(TlsError::InvalidCertificate(String::from("no valid certificates found in PEM data")))
+                }
+            }
+        }
+    }
+
+    match config.identity {
+        Option::Some(ClientIdentity::Pem{ ref certificate_chain, ref private_key }) => {
+            let chain = pemfile::certs(&mut BufReader::new(&certificate_chain[..]))
+                .ok()
+                .and_then(|chain| if chain.is_empty() { Option::None } else { Option::Some(chain) })
+                .ok_or_else(|| TlsError::InvalidIdentity(String::from("no certificates found in PEM data")))?;
+
+            let key = pemfile::pkcs8_private_keys(&mut BufReader::new(&private_key[..])).unwrap_or_default().into_iter()
+                .chain(pemfile::rsa_private_keys(&mut BufReader::new(&private_key[..])).unwrap_or_default())
+                .next()
+                .ok_or_else(|| TlsError::InvalidIdentity(String::from("no private key found in PEM data")))?;
+
+            // rustls panics on a key it can't use, so check it first
+            rustls::sign::any_supported_type(&key)
+                .map_err(|_| TlsError::InvalidIdentity(String::from("unsupported private key type")))?;
+
+            tls_config.set_single_client_cert(chain, key);
+        },
+        Option::Some(ClientIdentity::Pkcs12{ .. }) =>
+            return Result::Err(TlsError::Unsupported("PKCS#12 client identities, use PEM files with rustls")),
+        Option::None => ()
+    }
+
+    Result::Ok(Arc::new(tls_config))
+}
+
+pub fn ssl_client(config: &TlsConfig) -> Result<SslClient, TlsError> {
+    Result::Ok(TlsClient{ cfg: client_config(config)? })
+}
+
+pub fn async_connector(config: &TlsConfig, handle: &Handle) -> Result<AsyncConnector, TlsError> {
+    let mut http = HttpConnector::new(4, handle);
+    http.enforce_http(false);
+
+    Result::Ok(AsyncConnector{
+        http: http,
+        config: client_config(config)?,
+    })
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* Connector for hyper's async client that negotiates TLS with rustls for https urls
+*/
+pub struct AsyncConnector {
+    http: HttpConnector,
+    config: Arc<ClientConfig>,
+}
+
+impl Service for AsyncConnector {
+    type Request = Uri;
+    type Response = MaybeTlsStream;
+    type Error = io::Error;
+    type Future = Box<Future<Item=MaybeTlsStream, Error=io::Error>>;
+
+    fn call(&self, uri: Uri) -> Self::Future {
+
+        let is_https = uri.scheme() == Option::Some("https");
+        let host = String::from(uri.host().unwrap_or(""));
+        let config = self.config.clone();
+
+        Box::new(self.http.call(uri).and_then(move |tcp| {
+            if !is_https {
+                return Either::A(future::ok(MaybeTlsStream::Http(tcp)));
+            }
+
+            let domain = match DNSNameRef::try_from_ascii_str(&host) {
+                Result::Ok(domain) => domain,
+                Result::Err(_) => return Either::A(future::err(io::Error::new(io::ErrorKind::InvalidInput,
+                                                                              format!("Invalid DNS name: {}", host))))
+            };
+
+            Either::B(config.connect_async(domain, tcp).map(MaybeTlsStream::Https))
+        }))
+    }
+}
+
+/**
+* A connection that may or may not be wrapped in TLS
+*/
+pub enum MaybeTlsStream {
+    Http(TcpStream),
+    Https(TlsStream<TcpStream, ClientSession>),
+}
+
+impl Read for MaybeTlsStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match *self {
+            MaybeTlsStream::Http(ref mut stream) => stream.read(buf),
+            MaybeTlsStream::Https(ref mut stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for MaybeTlsStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match *self {
+            MaybeTlsStream::Http(ref mut stream) => stream.write(buf),
+            MaybeTlsStream::Https(ref mut stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match *self {
+            MaybeTlsStream::Http(ref mut stream) => stream.flush(),
+            MaybeTlsStream::Https(ref mut stream) => stream.flush(),
+        }
+    }
+}
+
+impl AsyncRead for MaybeTlsStream {}
+
+impl AsyncWrite for MaybeTlsStream {
+    fn shutdown(&mut self) -> Poll<(), io::Error> {
+        match *self {
+            MaybeTlsStream::Http(ref mut stream) => AsyncWrite::shutdown(stream),
+            MaybeTlsStream::Https(ref mut stream) => AsyncWrite::shutdown(stream),
+        }
+    }
+}
@@ -0,0 +1,628 @@
+use hyper;
+use hyper::Url;
+use hyper::client::{Pool, Request};
+use hyper::client::pool::Config;
+use hyper::header::ContentLength;
+use hyper::method::Method;
+use hyper::net::{HttpStream, HttpsConnector, NetworkConnector};
+
+use builder::ConfigProblem;
+use client::ApiError;
+use proxy::ProxyConfig;
+use tls::{self, SslClient, TlsConfig};
+
+use std::cell::Cell;
+use std::cmp;
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::mem;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* HTTP verbs used by the API.ai REST services.
+*
+*/
+#[derive(Clone,Debug,PartialEq)]
+pub enum HttpMethod {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
+
+/**
+* Timeouts applied to a request. `None` means wait indefinitely.
+*
+* `connect` bounds establishing a new connection, `read` bounds each read from (or write to) the
+* socket and `request` is the overall deadline for the whole exchange.
+*/
+#[derive(Clone,Debug,PartialEq)]
+pub struct Timeouts {
+    pub connect: Option<Duration>,
+    pub read: Option<Duration>,
+    pub request: Option<Duration>,
+}
+
+impl Default for Timeouts {
+    fn default() -> Timeouts {
+        Timeouts {
+            connect: Option::Some(Duration::from_secs(10)),
+            read: Option::Some(Duration::from_secs(30)),
+            request: Option::Some(Duration::from_secs(60)),
+        }
+    }
+}
+
+impl Timeouts {
+
+    /**
+    * Timeouts that never expire
+    */
+    pub fn none() -> Timeouts {
+        Timeouts {
+            connect: Option::None,
+            read: Option::None,
+            request: Option::None,
+        }
+    }
+
+    /**
+    * The connect timeout, shortened if the overall deadline is sooner
+    */
+    pub fn effective_connect(&self) -> Option<Duration> {
+        min_timeout(self.connect, self.request)
+    }
+
+    /**
+    * The read timeout, shortened if the overall deadline is sooner
+    */
+    pub fn effective_read(&self) -> Option<Duration> {
+        min_timeout(self.read, self.request)
+    }
+}
+
+fn min_timeout(a: Option<Duration>, b: Option<Duration>) -> Option<Duration> {
+    match (a, b) {
+        (Option::Some(a), Option::Some(b)) => Option::Some(cmp::min(a, b)),
+        (a, Option::None) => a,
+        (Option::None, b) => b
+    }
+}
+
+/**
+* A fully built HTTP request ready to be handed to an HttpTransport.
+*
+*/
+#[derive(Clone,Debug)]
+pub struct HttpRequest {
+    pub method: HttpMethod,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    pub timeouts: Timeouts,
+}
+
+/**
+* The raw HTTP response returned by an HttpTransport.
+*
+*/
+#[derive(Clone,Debug)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+
+    /**
+    * Look up the first value of a header by name (case insensitive).
+    */
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|&&(ref key, _)| key.eq_ignore_ascii_case(name))
+            .map(|&(_, ref value)| value.as_str())
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* HttpTransport is responsible for actually putting a request on the wire.
+*
+* ApiAIClient builds the request (URL, headers and JSON body) and hands it to the transport, which
+* returns the status, headers and body of the reply. Implementations must be safe to share across
+* threads.
+*/
+pub trait HttpTransport : Send + Sync {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse, ApiError>;
+
+    /**
+    * Send a request whose body is read from `body` as it is sent, ignoring `request.body`.
+    *
+    * The default implementation reads the whole body into memory and calls send.
+    */
+    fn send_stream(&self, request: HttpRequest, body: StreamingBody) -> Result<HttpResponse, ApiError> {
+        let mut request = request;
+        let mut reader = body.reader;
+
+        request.body.clear();
+        reader.read_to_end(&mut request.body).map_err(map_io_to_api_err)?;
+
+        self.send(request)
+    }
+}
+
+/**
+* A request body that is streamed from a reader rather than held in memory.
+*
+* If the `length` is known it is sent as the Content-Length, otherwise the body is sent with chunked
+* transfer encoding.
+*/
+pub struct StreamingBody {
+    pub reader: Box<Read + Send>,
+    pub length: Option<u64>,
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* Connection pool settings for HyperTransport.
+*
+* `max_idle` is the number of keep-alive connections kept open per host and `idle_timeout` is how
+* long an idle connection may sit in the pool before it is discarded.
+*/
+#[derive(Clone,Debug)]
+pub struct PoolConfig {
+    pub max_idle: usize,
+    pub idle_timeout: Option<Duration>,
+}
+
+impl Default for PoolConfig {
+    fn default() -> PoolConfig {
+        PoolConfig {
+            max_idle: 5,
+            idle_timeout: Option::Some(Duration::from_secs(90)),
+        }
+    }
+}
+
+/**
+* Where a HyperTransport sends its connections
+*
+*/
+#[derive(Clone,Debug,PartialEq)]
+pub enum ProxyMode {
+    /**
+    * Follow the HTTPS_PROXY and NO_PROXY environment variables
+    */
+    FromEnv,
+    Direct,
+    Via(ProxyConfig),
+}
+
+/**
+* The settings a HyperTransport is built from. ApiAIClient keeps them so that changing one with
+* a setter keeps the others.
+*/
+#[derive(Clone,Debug)]
+pub struct TransportSettings {
+    pub pool: PoolConfig,
+    pub proxy: ProxyMode,
+    pub tls: TlsConfig,
+}
+
+impl Default for TransportSettings {
+    fn default() -> TransportSettings {
+        TransportSettings {
+            pool: PoolConfig::default(),
+            proxy: ProxyMode::FromEnv,
+            tls: TlsConfig::default(),
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* The default transport, calls the API over HTTPS using hyper and the TLS backend chosen by cargo
+* features (native-tls unless the `rustls` feature is enabled).
+*
+* The TLS connector and connection pool are built once, on first use, so keep-alive connections
+* (and their TLS sessions) are reused between queries. If the TLS backend cannot be initialised the
+* send fails with ApiError::TlsInit and initialisation is tried again on the next call. A single
+* HyperTransport can be shared between threads.
+*
+* All requests share the one pool whatever their timeouts. The connect timeout bounds opening a
+* connection, including the TLS handshake, and the read timeout each write and read after it. Both
+* are cut to the time left before the request deadline as each phase starts. Redirects are
+* returned as they are rather than followed.
+*
+* Unless told otherwise connections go through the proxy named by the HTTPS_PROXY environment
+* variable, skipping the hosts listed in NO_PROXY. If HTTPS_PROXY can't be parsed every send fails
+* with ApiError::HttpError rather than connecting directly; ApiAIClientBuilder reports it as a
+* ConfigProblem instead.
+*/
+pub struct HyperTransport {
+    config: PoolConfig,
+    proxy: Option<ProxyConfig>,
+    proxy_problem: Option<ConfigProblem>,
+    tls: TlsConfig,
+    pool: Mutex<Option<Arc<HttpsPool>>>,
+}
+
+type HttpsPool = Pool<HttpsConnector<SslClient, TimeoutConnector>>;
+
+impl Default for HyperTransport {
+    fn default() -> HyperTransport {
+        HyperTransport::new()
+    }
+}
+
+impl HyperTransport {
+
+    pub fn new() -> HyperTransport {
+        HyperTransport::with_pool_config(PoolConfig::default())
+    }
+
+    pub fn with_pool_config(config: PoolConfig) -> HyperTransport {
+        HyperTransport::from_settings(TransportSettings{ pool: config, ..Default::default() })
+    }
+
+    pub fn from_settings(settings: TransportSettings) -> HyperTransport {
+        match settings.proxy {
+            ProxyMode::FromEnv => match ProxyConfig::from_env() {
+                Result::Ok(proxy) => HyperTransport::with_settings(settings.pool, proxy, settings.tls),
+                Result::Err(problem) => HyperTransport{
+                    proxy_problem: Option::Some(problem),
+                    ..HyperTransport::with_settings(settings.pool, Option::None, settings.tls)
+                }
+            },
+            ProxyMode::Direct => HyperTransport::with_settings(settings.pool, Option::None, settings.tls),
+            ProxyMode::Via(proxy) => HyperTransport::with_settings(settings.pool, Option::Some(proxy), settings.tls),
+        }
+    }
+
+    /**
+    * Create a transport with the given pool and TLS settings that connects through `proxy`, or
+    * directly if it is None.
+    */
+    pub fn with_settings(config: PoolConfig, proxy: Option<ProxyConfig>, tls: TlsConfig) -> HyperTransport {
+        HyperTransport {
+            config: config,
+            proxy: proxy,
+            proxy_problem: Option::None,
+            tls: tls,
+            pool: Mutex::new(Option::None),
+        }
+    }
+
+    /**
+    * Get the shared connection pool, building the TLS connector and pool if this is the first call.
+    */
+    fn pool(&self) -> Result<Arc<HttpsPool>, ApiError> {
+
+        if let Option::Some(ref problem) = self.proxy_problem {
+            return Result::Err(ApiError::HttpError(problem.to_string()));
+        }
+
+        let mut shared = self.pool.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if let Option::Some(ref pool) = *shared {
+            return Result::Ok(pool.clone());
+        }
+
+        let ssl = tls::ssl_client(&self.tls).map_err(ApiError::TlsInit)?;
+        let connector = HttpsConnector::with_connector(ssl, TimeoutConnector{ proxy: self.proxy.clone() });
+
+        let mut pool = Pool::with_connector(Config { max_idle: self.config.max_idle }, connector);
+        pool.set_idle_timeout(self.config.idle_timeout);
+
+        let pool = Arc::new(pool);
+        *shared = Option::Some(pool.clone());
+
+        Result::Ok(pool)
+    }
+}
+
+impl HyperTransport {
+
+    fn exchange(&self, request: HttpRequest, body: &mut Read, length: Option<u64>) -> Result<HttpResponse, ApiError> {
+
+        let started = Instant::now();
+        let timeouts = request.timeouts;
+        let pool = self.pool()?;
+
+        let url = Url::parse(&request.url).map_err(|err| map_hyper_to_api_err(hyper::Error::from(err)))?;
+        let method = match request.method {
+            HttpMethod::Get => Method::Get,
+            HttpMethod::Post => Method::Post,
+            HttpMethod::Put => Method::Put,
+            HttpMethod::Delete => Method::Delete,
+        };
+        let has_body = method != Method::Get;
+
+        CONNECT_TIMEOUT.with(|timeout| timeout.set(timeouts.effective_connect()));
+        let mut outgoing = Request::with_connector(method, url, &*pool).map_err(map_hyper_to_api_err)?;
+
+        for (name, value) in request.headers {
+            outgoing.headers_mut().append_raw(name, value.into_bytes());
+        }
+        if let (true, Option::Some(length)) = (has_body, length) {
+            outgoing.headers_mut().set(ContentLength(length));
+        }
+
+        let timeout = socket_timeout(&timeouts, started)?;
+        outgoing.set_write_timeout(timeout).map_err(map_io_to_api_err)?;
+        let mut outgoing = outgoing.start()
+            .map_err(|err| phase_error(hyper_io_error(err), &timeouts, timeout))?;
+
+        if has_body {
+            let mut buf = [0; 8192];
+            loop {
+                let read = match body.read(&mut buf) {
+                    Result::Ok(0) => break,
+                    Result::Ok(read) => read,
+                    Result::Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                    Result::Err(err) => return Result::Err(ApiError::Io(err))
+                };
+                let timeout = socket_timeout(&timeouts, started)?;
+                outgoing.set_write_timeout(timeout).map_err(map_io_to_api_err)?;
+                outgoing.write_all(&buf[..read]).map_err(|err| phase_error(Result::Ok(err), &timeouts, timeout))?;
+            }
+        }
+
+        // sending finishes the body, then the response head is read
+        let timeout = socket_timeout(&timeouts, started)?;
+        outgoing.set_write_timeout(timeout).map_err(map_io_to_api_err)?;
+        outgoing.set_read_timeout(timeout).map_err(map_io_to_api_err)?;
+        let mut response = outgoing.send()
+            .map_err(|err| phase_error(hyper_io_error(err), &timeouts, timeout))?;
+
+        let body = read_body(&mut response, &timeouts, started)?;
+
+        Result::Ok(HttpResponse {
+            status: response.status.to_u16(),
+            headers: response.headers.iter()
+                .map(|header| (String::from(header.name()), header.value_string()))
+                .collect(),
+            body: body,
+        })
+    }
+}
+
+impl HttpTransport for HyperTransport {
+
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse, ApiError> {
+        let mut request = request;
+        let body = mem::replace(&mut request.body, Vec::new());
+        let length = body.len() as u64;
+
+        self.exchange(request, &mut &body[..], Option::Some(length))
+    }
+
+    fn send_stream(&self, request: HttpRequest, body: StreamingBody) -> Result<HttpResponse, ApiError> {
+        let mut reader = body.reader;
+
+        self.exchange(request, &mut *reader, body.length)
+    }
+}
+
+/**
+* The socket timeout for the next step of an exchange: the read timeout, cut to the time left
+* before the request deadline. Fails once the deadline has passed.
+*/
+fn socket_timeout(timeouts: &Timeouts, started: Instant) -> Result<Option<Duration>, ApiError> {
+    let remaining = match timeouts.request {
+        Option::Some(deadline) => match deadline.checked_sub(started.elapsed()) {
+            Option::Some(remaining) if remaining > Duration::from_millis(0) => Option::Some(remaining),
+            _ => return Result::Err(deadline_exceeded(deadline))
+        },
+        Option::None => Option::None
+    };
+
+    Result::Ok(min_timeout(timeouts.read, remaining))
+}
+
+/**
+* Map a failed step onto an ApiError, reporting a timeout that was cut short by the request
+* deadline as the deadline passing
+*/
+fn phase_error(err: Result<io::Error, ApiError>, timeouts: &Timeouts, timeout: Option<Duration>) -> ApiError {
+    match (err, timeouts.request) {
+        (Result::Ok(ref err), Option::Some(deadline)) if is_timeout(err) && timeout != timeouts.read => deadline_exceeded(deadline),
+        (Result::Ok(err), _) => map_io_to_api_err(err),
+        (Result::Err(err), _) => err
+    }
+}
+
+/**
+* The socket error behind a hyper error, or the ApiError for any other failure
+*/
+fn hyper_io_error(err: hyper::Error) -> Result<io::Error, ApiError> {
+    match err {
+        hyper::Error::Io(err) => Result::Ok(err),
+        err => Result::Err(map_hyper_to_api_err(err))
+    }
+}
+
+/**
+* Read the response body, lowering the socket read timeout before each read so that the whole
+* exchange finishes by the request deadline however slowly the server sends.
+*/
+fn read_body(response: &mut hyper::client::Response, timeouts: &Timeouts, started: Instant) -> Result<Vec<u8>, ApiError> {
+
+    let mut body = Vec::new();
+    let mut buf = [0; 8192];
+
+    loop {
+        let read_timeout = socket_timeout(timeouts, started)?;
+        response.get_ref().set_read_timeout(read_timeout).map_err(map_io_to_api_err)?;
+
+        match response.read(&mut buf) {
+            Result::Ok(0) => return Result::Ok(body),
+            Result::Ok(read) => body.extend_from_slice(&buf[..read]),
+            Result::Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {},
+            Result::Err(err) => return Result::Err(phase_error(Result::Ok(err), timeouts, read_timeout))
+        }
+    }
+}
+
+thread_local! {
+    /**
+    * The connect timeout for the request being sent on this thread. hyper connects on the calling
+    * thread, from a connector shared by all requests, so it is handed over here.
+    */
+    static CONNECT_TIMEOUT: Cell<Option<Duration>> = Cell::new(Option::None)
+}
+
+/**
+* Plain TCP connector that gives up on establishing a connection after the calling request's connect
+* timeout, tunnelling through a proxy if one is configured for the host.
+*/
+struct TimeoutConnector {
+    proxy: Option<ProxyConfig>,
+}
+
+impl NetworkConnector for TimeoutConnector {
+    type Stream = HttpStream;
+
+    fn connect(&self, host: &str, port: u16, scheme: &str) -> hyper::Result<HttpStream> {
+
+        if scheme != "http" {
+            return Result::Err(hyper::Error::Io(io::Error::new(io::ErrorKind::InvalidInput, "Invalid scheme for Http")));
+        }
+
+        let connect_timeout = CONNECT_TIMEOUT.with(|timeout| timeout.get());
+
+        let stream = match self.proxy {
+            Option::Some(ref proxy) if proxy.applies_to(host) => proxy.tunnel(host, port, connect_timeout)?,
+            _ => connect_with_timeout(host, port, connect_timeout)?
+        };
+
+        // the TLS handshake that follows is part of connecting
+        stream.set_read_timeout(connect_timeout)?;
+        stream.set_write_timeout(connect_timeout)?;
+
+        Result::Ok(HttpStream(stream))
+    }
+}
+
+/**
+* Open a TCP connection, trying each address the host resolves to within the timeout.
+*/
+pub(crate) fn connect_with_timeout(host: &str, port: u16, timeout: Option<Duration>) -> io::Result<TcpStream> {
+
+    let timeout = match timeout {
+        Option::Some(timeout) => timeout,
+        Option::None => return TcpStream::connect((host, port))
+    };
+
+    let mut last_err = io::Error::new(io::ErrorKind::InvalidInput, "Could not resolve host");
+    for addr in (host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Result::Ok(stream) => return Result::Ok(stream),
+            Result::Err(err) => last_err = err
+        }
+    }
+
+    Result::Err(last_err)
+}
+
+fn map_hyper_to_api_err(err : hyper::Error) ->ApiError {
+    match err {
+        hyper::Error::Io(err) => map_io_to_api_err(err),
+        err => ApiError::HttpError(err.to_string())
+    }
+}
+
+/**
+* Map a socket error onto an ApiError, picking out timeouts.
+*
+* Depending on the platform a socket timeout surfaces as either TimedOut or WouldBlock.
+*/
+pub(crate) fn map_io_to_api_err(err : io::Error) -> ApiError {
+    if is_timeout(&err) {
+        ApiError::Timeout(err)
+    } else {
+        ApiError::Io(err)
+    }
+}
+
+fn is_timeout(err: &io::Error) -> bool {
+    match err.kind() {
+        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => true,
+        _ => false
+    }
+}
+
+/**
+* The error returned when the overall deadline for a request has passed
+*/
+pub(crate) fn deadline_exceeded(deadline: Duration) -> ApiError {
+    ApiError::Timeout(io::Error::new(io::ErrorKind::TimedOut,
+                                     format!("Request deadline of {:?} exceeded", deadline)))
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* An in-memory transport that never touches the network.
+*
+* Responses are queued up front and handed back in order; every request sent through the
+* transport is recorded so that tests can inspect what the client put on the wire.
+*/
+#[derive(Default)]
+pub struct MemoryTransport {
+    responses: Mutex<VecDeque<HttpResponse>>,
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+impl MemoryTransport {
+
+    pub fn new() -> MemoryTransport {
+        MemoryTransport::default()
+    }
+
+    /**
+    * Queue a response to be returned by the next call to send.
+    */
+    pub fn push_response(&self, response: HttpResponse) {
+        self.responses.lock().unwrap().push_back(response);
+    }
+
+    /**
+    * Queue a response with the given status code and JSON body.
+    */
+    pub fn push_json(&self, status: u16, body: &str) {
+        self.push_response(HttpResponse {
+            status: status,
+            headers: vec![(String::from("Content-Type"), String::from("application/json"))],
+            body: body.as_bytes().to_vec(),
+        });
+    }
+
+    /**
+    * All of the requests that have been sent through this transport so far.
+    */
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl HttpTransport for MemoryTransport {
+
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse, ApiError> {
+        self.requests.lock().unwrap().push(request);
+
+        match self.responses.lock().unwrap().pop_front() {
+            Option::Some(response) => Result::Ok(response),
+            Option::None => Result::Err(ApiError::HttpError(String::from("No response queued on MemoryTransport")))
+        }
+    }
+}
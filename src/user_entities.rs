@@ -0,0 +1,56 @@
+use client::{ApiAIClient, ApiError, json_body, parse_body};
+use structure::UserEntity;
+use transport::HttpMethod;
+
+
+/**
+* The userEntities endpoint manages entities that only apply to one session. They can also be sent
+* inline with a query through `ApiRequest.entities`.
+*
+* User entities expire along with the session.
+*/
+impl ApiAIClient {
+
+    /**
+    * Add user entities to a session. The session id of each entity is set to `session_id`.
+    *
+    */
+    pub fn create_user_entities(&self, session_id: &str, entities: &[UserEntity]) -> Result<(), ApiError>{
+        let entities: Vec<UserEntity> = entities.iter()
+            .cloned()
+            .map(|mut entity| { entity.session_id = String::from(session_id); entity })
+            .collect();
+        let body = json_body(&entities)?;
+
+        self.call_endpoint(HttpMethod::Post, &["userEntities"], &[("sessionId", session_id)], Option::Some(body))
+            .map(|_| ())
+    }
+
+    /**
+    * Get a user entity from a session. Fails with ApiError::NotFound if the session has none by
+    * that name.
+    *
+    */
+    pub fn user_entity(&self, session_id: &str, name: &str) -> Result<UserEntity, ApiError>{
+        self.call_endpoint(HttpMethod::Get, &["userEntities", name], &[("sessionId", session_id)], Option::None)
+            .and_then(parse_body)
+    }
+
+    /**
+    * Replace the user entity called `name` in a session
+    *
+    */
+    pub fn update_user_entity(&self, session_id: &str, name: &str, entity: &UserEntity) -> Result<(), ApiError>{
+        let mut entity = entity.clone();
+        entity.session_id = String::from(session_id);
+        let body = json_body(&entity)?;
+
+        self.call_endpoint(HttpMethod::Put, &["userEntities", name], &[("sessionId", session_id)], Option::Some(body))
+            .map(|_| ())
+    }
+
+    pub fn delete_user_entity(&self, session_id: &str, name: &str) -> Result<(), ApiError>{
+        self.call_endpoint(HttpMethod::Delete, &["userEntities", name], &[("sessionId", session_id)], Option::None)
+            .map(|_| ())
+    }
+}
@@ -0,0 +1,232 @@
+use chrono::UTC;
+use serde_json::{self, Value};
+
+use client::ApiError;
+use structure::{ApiContext, ApiFulfillment, ApiMessage, ApiMetadata, ApiRequest, ApiResponse, ApiResult, ApiStatus,
+                Card, CardButton, CustomPayload, ImageResponse, QuickReplies, TextResponse};
+use super::SessionPath;
+use super::structure::{DetectIntentRequest, DetectIntentResponse, EventInput, FulfillmentMessage, LatLng, OutputContext,
+                       QueryInput, QueryParameters, SessionEntity, SessionEntityType, TextInput};
+
+
+impl DetectIntentRequest {
+
+    /**
+    * Build the v2 equivalent of a v1 query, to be sent for `session`.
+    *
+    * Context and user entity names are expanded into full paths under the session. The original
+    * request has no counterpart in detectIntent and is left out.
+    *
+    * A query with neither a query text nor an event, or for an empty session, can't be sent and
+    * gives ApiError::InvalidRequest.
+    */
+    pub fn from_api_request(request: &ApiRequest, session: &SessionPath) -> Result<DetectIntentRequest, ApiError> {
+
+        if session.session.is_empty() {
+            return Result::Err(ApiError::InvalidRequest(String::from("a session id is needed for detectIntent")));
+        }
+
+        let language_code = String::from(request.lang.value());
+
+        let query_input = match (&request.query, &request.event) {
+            (&Option::Some(ref text), _) => QueryInput{
+                text: Option::Some(TextInput{ text: text.clone(), language_code: language_code }),
+                ..Default::default()
+            },
+            (&Option::None, &Option::Some(ref event)) => QueryInput{
+                event: Option::Some(EventInput{
+                    name: event.name.clone(),
+                    parameters: event.data.clone().unwrap_or_default(),
+                    language_code: language_code
+                }),
+                ..Default::default()
+            },
+            (&Option::None, &Option::None) =>
+                return Result::Err(ApiError::InvalidRequest(String::from("either a query or an event is needed for detectIntent")))
+        };
+
+        let params = QueryParameters{
+            time_zone: request.timezone.clone(),
+            geo_location: request.location.as_ref()
+                .map(|location| LatLng{ latitude: location.latitude, longitude: location.longitude }),
+            contexts: request.contexts.iter()
+                .map(|context| OutputContext{
+                    name: session.context_name(&context.name),
+                    lifespan_count: context.lifespan,
+                    parameters: context.parameters.clone(),
+                })
+                .collect(),
+            session_entity_types: request.entities.iter()
+                .map(|entity| SessionEntityType{
+                    name: session.entity_type_name(&entity.name),
+                    entity_override_mode: String::from(if entity.extend {
+                        "ENTITY_OVERRIDE_MODE_SUPPLEMENT"
+                    } else {
+                        "ENTITY_OVERRIDE_MODE_OVERRIDE"
+                    }),
+                    entities: entity.entries.iter()
+                        .map(|entry| SessionEntity{ value: entry.value.clone(), synonyms: entry.synonyms.clone() })
+                        .collect(),
+                })
+                .collect(),
+            reset_contexts: request.reset_contexts,
+        };
+
+        let query_params = if params.time_zone.is_none() && params.geo_location.is_none() && params.reset_contexts.is_none()
+            && params.contexts.is_empty() && params.session_entity_types.is_empty() {
+            Option::None
+        } else {
+            Option::Some(params)
+        };
+
+        Result::Ok(DetectIntentRequest{
+            query_params: query_params,
+            query_input: query_input,
+        })
+    }
+}
+
+impl DetectIntentResponse {
+
+    /**
+    * Convert to the v1 response shape so that code written against ApiAIClient::query keeps
+    * working.
+    *
+    * A failed webhook call is reported as v1 did with a 206 status. Text, image, quick replies,
+    * card and custom payload messages are carried over; the Actions on Google messages are not.
+    */
+    pub fn into_api_response(self, session_id: &str) -> ApiResponse {
+
+        let result = self.query_result;
+
+        let status = match self.webhook_status {
+            Option::Some(ref status) if status.code != 0 => ApiStatus{
+                code: 206,
+                error_type: String::from("partial_content"),
+                error_details: Option::Some(format!("Webhook call failed. Error: {}", status.message)),
+                ..Default::default()
+            },
+            _ => ApiStatus::default()
+        };
+
+        let messages: Vec<ApiMessage> = result.fulfillment_messages.iter()
+            .flat_map(to_api_messages)
+            .collect();
+
+        let (intent_id, intent_name) = match result.intent {
+            Option::Some(intent) => (Option::Some(last_segment(&intent.name)), Option::Some(intent.display_name)),
+            Option::None => (Option::None, Option::None)
+        };
+
+        ApiResponse{
+            id: self.response_id,
+            timestamp: UTC::now().format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+            lang: serde_json::from_value(Value::String(result.language_code)).unwrap_or_default(),
+            result: ApiResult{
+                source: String::from("agent"),
+                resolved_query: result.query_text,
+                action: result.action,
+                action_incomplete: !result.all_required_params_present,
+                parameters: result.parameters,
+                contexts: result.output_contexts.iter()
+                    .map(|context| ApiContext{
+                        name: last_segment(&context.name),
+                        parameters: context.parameters.clone(),
+                        lifespan: context.lifespan_count,
+                        ..Default::default()
+                    })
+                    .collect(),
+                metadata: ApiMetadata{
+                    intent_id: intent_id,
+                    webhook_used: self.webhook_status.is_some().to_string(),
+                    webhook_slotfilling_used: String::from("false"),
+                    intent_name: intent_name,
+                    ..Default::default()
+                },
+                fulfillment: ApiFulfillment{
+                    speech: result.fulfillment_text,
+                    messages: if messages.is_empty() { Option::None } else { Option::Some(messages) },
+                    ..Default::default()
+                },
+                score: result.intent_detection_confidence,
+                ..Default::default()
+            },
+            status: status,
+            session_id: String::from(session_id),
+            ..Default::default()
+        }
+    }
+}
+
+fn to_api_messages(message: &FulfillmentMessage) -> Vec<ApiMessage> {
+    let platform = to_api_platform(message.platform.as_ref());
+    let mut messages = Vec::new();
+
+    if let Option::Some(ref text) = message.text {
+        messages.extend(text.text.iter().map(|speech| ApiMessage::Text(TextResponse{
+            speech: vec![speech.clone()],
+            platform: platform.clone(),
+            ..Default::default()
+        })));
+    }
+    if let Option::Some(ref image) = message.image {
+        messages.push(ApiMessage::Image(ImageResponse{
+            image_url: image.image_uri.clone(),
+            platform: platform.clone(),
+            ..Default::default()
+        }));
+    }
+    if let Option::Some(ref quick_replies) = message.quick_replies {
+        messages.push(ApiMessage::QuickReplies(QuickReplies{
+            title: non_empty(&quick_replies.title),
+            replies: quick_replies.quick_replies.clone(),
+            platform: platform.clone(),
+            ..Default::default()
+        }));
+    }
+    if let Option::Some(ref card) = message.card {
+        messages.push(ApiMessage::Card(Card{
+            title: card.title.clone(),
+            subtitle: non_empty(&card.subtitle),
+            image_url: non_empty(&card.image_uri),
+            buttons: card.buttons.iter()
+                .map(|button| CardButton{
+                    text: button.text.clone(),
+                    postback: non_empty(&button.postback),
+                    ..Default::default()
+                })
+                .collect(),
+            platform: platform.clone(),
+            ..Default::default()
+        }));
+    }
+    if let Option::Some(ref payload) = message.payload {
+        messages.push(ApiMessage::CustomPayload(CustomPayload{
+            payload: Value::Object(payload.clone()),
+            platform: platform.clone(),
+            ..Default::default()
+        }));
+    }
+
+    messages
+}
+
+/**
+* The v1 name of a v2 platform, e.g. `facebook` for `FACEBOOK`. Messages for any platform have
+* none.
+*/
+fn to_api_platform(platform: Option<&String>) -> Option<String> {
+    match platform.map(String::as_str) {
+        Option::None | Option::Some("") | Option::Some("PLATFORM_UNSPECIFIED") => Option::None,
+        Option::Some("ACTIONS_ON_GOOGLE") => Option::Some(String::from("google")),
+        Option::Some(platform) => Option::Some(platform.to_lowercase())
+    }
+}
+
+fn non_empty(text: &str) -> Option<String> {
+    if text.is_empty() { Option::None } else { Option::Some(String::from(text)) }
+}
+
+fn last_segment(path: &str) -> String {
+    String::from(path.rsplit('/').next().unwrap_or(path))
+}
@@ -0,0 +1,228 @@
+mod structure;
+mod convert;
+
+pub use self::structure::{
+        DetectIntentRequest,
+        DetectIntentResponse,
+        QueryParameters,
+        LatLng,
+        QueryInput,
+        TextInput,
+        EventInput,
+        SessionEntityType,
+        SessionEntity,
+        QueryResult,
+        OutputContext,
+        IntentRef,
+        FulfillmentMessage,
+        TextMessage,
+        ImageMessage,
+        QuickRepliesMessage,
+        CardMessage,
+        CardMessageButton,
+        RpcStatus,
+};
+
+use serde_json;
+
+use auth::{AuthError, AuthProvider, ServiceAccountProvider, StaticToken};
+use client::{ApiError, json_body, parse_body, status_error, is_success};
+use structure::{ApiRequest, ApiResponse};
+use transport::{HttpTransport, HttpRequest, HttpResponse, HttpMethod, HyperTransport, Timeouts};
+
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+
+pub static DEFAULT_BASE_URL: &'static str = "https://dialogflow.googleapis.com/v2";
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* A conversation with an agent, named `projects/{project}/agent/sessions/{session}` by the v2 API
+*
+*/
+#[derive(Clone,Debug,PartialEq)]
+pub struct SessionPath {
+    pub project: String,
+    pub session: String,
+}
+
+impl SessionPath {
+
+    pub fn new(project: &str, session: &str) -> SessionPath {
+        SessionPath{ project: String::from(project), session: String::from(session) }
+    }
+
+    /**
+    * Parse a full session path, returning None if it isn't one
+    */
+    pub fn parse(path: &str) -> Option<SessionPath> {
+        let parts: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+        if parts.len() == 5 && parts[0] == "projects" && parts[2] == "agent" && parts[3] == "sessions"
+            && !parts[1].is_empty() && !parts[4].is_empty() {
+            Option::Some(SessionPath::new(parts[1], parts[4]))
+        } else {
+            Option::None
+        }
+    }
+
+    /**
+    * The full name of a context in this session
+    */
+    pub fn context_name(&self, context: &str) -> String {
+        format!("{}/contexts/{}", self, context)
+    }
+
+    /**
+    * The full name of a session entity type in this session
+    */
+    pub fn entity_type_name(&self, entity_type: &str) -> String {
+        format!("{}/entityTypes/{}", self, entity_type)
+    }
+}
+
+impl fmt::Display for SessionPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "projects/{}/agent/sessions/{}", self.project, self.session)
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* DialogflowClient calls the Dialogflow v2 detectIntent method for the agent of a Google Cloud
+* project. api.ai became Dialogflow and its v1 API has been shut down.
+*
+* `detect_intent` speaks the v2 types directly, while `query` takes and returns the v1 ApiRequest
+* and ApiResponse so that callers of ApiAIClient::query can move over gradually.
+*
+* Requests are authenticated with OAuth2 access tokens from `auth`. Use a ServiceAccountProvider
+* to have tokens fetched and refreshed for a service account, or a StaticToken for a token obtained
+* elsewhere (e.g. from `gcloud auth print-access-token`), bearing in mind that those expire after
+* an hour.
+*
+* Requests are put on the wire by an HttpTransport, like ApiAIClient. Failures are mapped onto the
+* same ApiError variants, with the Google error status (e.g. `PERMISSION_DENIED`) as the error
+* type. Queries are not retried.
+*/
+#[derive(Clone)]
+pub struct DialogflowClient {
+    pub project: String,
+    pub auth: Arc<AuthProvider>,
+    pub base_url: String,
+    pub transport: Arc<HttpTransport>,
+    pub timeouts: Timeouts,
+}
+
+impl Default for DialogflowClient {
+    fn default() -> DialogflowClient {
+        DialogflowClient{
+            project: String::new(),
+            auth: Arc::new(StaticToken(String::new())),
+            base_url: String::from(DEFAULT_BASE_URL),
+            transport: Arc::new(HyperTransport::new()),
+            timeouts: Timeouts::default(),
+        }
+    }
+}
+
+/**
+* Just enough of an error body to read the status from it
+*/
+#[derive(Deserialize)]
+struct ErrorEnvelope {
+    error: RpcStatus,
+}
+
+impl DialogflowClient {
+
+    /**
+    * Create a client that sends a fixed access token
+    *
+    */
+    pub fn new(project: &str, access_token: &str) -> DialogflowClient {
+        DialogflowClient::with_auth(project, Arc::new(StaticToken(String::from(access_token))))
+    }
+
+    pub fn with_auth(project: &str, auth: Arc<AuthProvider>) -> DialogflowClient {
+        DialogflowClient{
+            project: String::from(project),
+            auth: auth,
+            ..Default::default()
+        }
+    }
+
+    /**
+    * Create a client for the project of a service account, authenticating as that account with
+    * the key in the given JSON key file.
+    *
+    */
+    pub fn from_service_account_file<P: AsRef<Path>>(path: P) -> Result<DialogflowClient, AuthError> {
+        let provider = ServiceAccountProvider::from_file(path)?;
+        let project = provider.key().project_id.clone();
+
+        Result::Ok(DialogflowClient::with_auth(&project, Arc::new(provider)))
+    }
+
+    /**
+    * The path of a session of this client's project
+    */
+    pub fn session(&self, session_id: &str) -> SessionPath {
+        SessionPath::new(&self.project, session_id)
+    }
+
+    /**
+    * Match a query against the agent within the given session
+    *
+    */
+    pub fn detect_intent(&self, session: &SessionPath, request: &DetectIntentRequest) -> Result<DetectIntentResponse, ApiError> {
+
+        let request = HttpRequest {
+            method: HttpMethod::Post,
+            url: format!("{}/{}:detectIntent", self.base_url, session),
+            headers: vec![
+                (String::from("Authorization"), format!("Bearer {}", self.auth.access_token()?)),
+                (String::from("Content-Type"), String::from("application/json")),
+            ],
+            body: json_body(request)?,
+            timeouts: self.timeouts.clone(),
+        };
+
+        self.transport.send(request)
+            .and_then(check_status)
+            .and_then(parse_body)
+    }
+
+    /**
+    * Carry out a v1 style query through detectIntent, in the session named by the request's
+    * session id.
+    *
+    */
+    pub fn query(&self, query: ApiRequest) -> Result<ApiResponse, ApiError> {
+        let session = self.session(&query.session_id);
+        let request = DetectIntentRequest::from_api_request(&query, &session)?;
+
+        self.detect_intent(&session, &request)
+            .map(|response| response.into_api_response(&query.session_id))
+    }
+}
+
+/**
+* Map an error reply, which carries a Google status rather than an api.ai one, onto an ApiError
+*/
+fn check_status(response: HttpResponse) -> Result<HttpResponse, ApiError> {
+
+    if is_success(response.status) {
+        return Result::Ok(response);
+    }
+
+    let (error_type, error_details) = match serde_json::from_slice::<ErrorEnvelope>(&response.body) {
+        Result::Ok(envelope) => (envelope.error.status.unwrap_or_default(), Option::Some(envelope.error.message)),
+        Result::Err(_) => (String::new(), Option::None)
+    };
+
+    Result::Err(status_error(&response, response.status, error_type, error_details))
+}
@@ -0,0 +1,263 @@
+use parameters::Parameters;
+use serde_json::{Map, Value};
+
+/**
+* Request body for the v2 detectIntent method
+*
+*/
+#[derive(Clone,Serialize,Deserialize)]
+pub struct DetectIntentRequest{
+    #[serde(rename = "queryParams", skip_serializing_if="Option::is_none", default)]
+    pub query_params: Option<QueryParameters>,
+    #[serde(rename = "queryInput")]
+    pub query_input: QueryInput,
+}
+
+/**
+* Settings that apply to a single detectIntent call
+*
+*/
+#[derive(Clone,Default,Serialize,Deserialize)]
+pub struct QueryParameters{
+    #[serde(rename = "timeZone", skip_serializing_if="Option::is_none", default)]
+    pub time_zone: Option<String>,
+    #[serde(rename = "geoLocation", skip_serializing_if="Option::is_none", default)]
+    pub geo_location: Option<LatLng>,
+    /**
+    * Contexts to activate before the query is matched. Names are full context paths.
+    */
+    #[serde(skip_serializing_if="Vec::is_empty", default)]
+    pub contexts: Vec<OutputContext>,
+    #[serde(rename = "resetContexts", skip_serializing_if="Option::is_none", default)]
+    pub reset_contexts: Option<bool>,
+    #[serde(rename = "sessionEntityTypes", skip_serializing_if="Vec::is_empty", default)]
+    pub session_entity_types: Vec<SessionEntityType>,
+}
+
+#[derive(Clone,Debug,PartialEq,Serialize,Deserialize)]
+pub struct LatLng{
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/**
+* What to match: either text typed by the user or an event. Exactly one should be set.
+*
+*/
+#[derive(Clone,Default,Serialize,Deserialize)]
+pub struct QueryInput{
+    #[serde(skip_serializing_if="Option::is_none", default)]
+    pub text: Option<TextInput>,
+    #[serde(skip_serializing_if="Option::is_none", default)]
+    pub event: Option<EventInput>,
+}
+
+impl QueryInput {
+
+    pub fn text(text: &str, language_code: &str) -> QueryInput {
+        QueryInput{
+            text: Option::Some(TextInput{ text: String::from(text), language_code: String::from(language_code) }),
+            ..Default::default()
+        }
+    }
+
+    pub fn event(name: &str, language_code: &str) -> QueryInput {
+        QueryInput{
+            event: Option::Some(EventInput{
+                name: String::from(name),
+                parameters: Parameters::new(),
+                language_code: String::from(language_code)
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Clone,Serialize,Deserialize)]
+pub struct TextInput{
+    pub text: String,
+    #[serde(rename = "languageCode")]
+    pub language_code: String,
+}
+
+#[derive(Clone,Serialize,Deserialize)]
+pub struct EventInput{
+    pub name: String,
+    #[serde(skip_serializing_if="Parameters::is_empty", default)]
+    pub parameters: Parameters,
+    #[serde(rename = "languageCode")]
+    pub language_code: String,
+}
+
+/**
+* Entity values that apply to one session, the v2 counterpart of a user entity
+*
+*/
+#[derive(Clone,Serialize,Deserialize)]
+pub struct SessionEntityType{
+    /**
+    * `projects/{p}/agent/sessions/{s}/entityTypes/{entity type name}`
+    */
+    pub name: String,
+    /**
+    * `ENTITY_OVERRIDE_MODE_OVERRIDE` or `ENTITY_OVERRIDE_MODE_SUPPLEMENT`
+    */
+    #[serde(rename = "entityOverrideMode")]
+    pub entity_override_mode: String,
+    pub entities: Vec<SessionEntity>,
+}
+
+#[derive(Clone,Serialize,Deserialize)]
+pub struct SessionEntity{
+    pub value: String,
+    #[serde(default)]
+    pub synonyms: Vec<String>,
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* Response from the v2 detectIntent method
+*
+*/
+#[derive(Clone,Serialize,Deserialize)]
+pub struct DetectIntentResponse{
+    #[serde(rename = "responseId", default)]
+    pub response_id: String,
+    #[serde(rename = "queryResult")]
+    pub query_result: QueryResult,
+    /**
+    * Present if a webhook was called, with the outcome of the call
+    */
+    #[serde(rename = "webhookStatus", skip_serializing_if="Option::is_none", default)]
+    pub webhook_status: Option<RpcStatus>,
+}
+
+/**
+* The intent matched by a query and what it produced
+*
+*/
+#[derive(Clone,Serialize,Deserialize)]
+pub struct QueryResult{
+    #[serde(rename = "queryText", default)]
+    pub query_text: String,
+    #[serde(rename = "languageCode", default)]
+    pub language_code: String,
+    #[serde(default)]
+    pub action: String,
+    #[serde(default)]
+    pub parameters: Parameters,
+    #[serde(rename = "allRequiredParamsPresent", default)]
+    pub all_required_params_present: bool,
+    #[serde(rename = "fulfillmentText", default)]
+    pub fulfillment_text: String,
+    #[serde(rename = "fulfillmentMessages", default)]
+    pub fulfillment_messages: Vec<FulfillmentMessage>,
+    #[serde(rename = "outputContexts", default)]
+    pub output_contexts: Vec<OutputContext>,
+    #[serde(skip_serializing_if="Option::is_none", default)]
+    pub intent: Option<IntentRef>,
+    #[serde(rename = "intentDetectionConfidence", default)]
+    pub intent_detection_confidence: f32,
+}
+
+/**
+* A context, named by its full path `projects/{p}/agent/sessions/{s}/contexts/{name}`
+*
+*/
+#[derive(Clone,Serialize,Deserialize)]
+pub struct OutputContext{
+    pub name: String,
+    #[serde(rename = "lifespanCount", skip_serializing_if="Option::is_none", default)]
+    pub lifespan_count: Option<i32>,
+    #[serde(skip_serializing_if="Parameters::is_empty", default)]
+    pub parameters: Parameters,
+}
+
+/**
+* The matched intent, named by its path `projects/{p}/agent/intents/{id}`
+*
+*/
+#[derive(Clone,Serialize,Deserialize)]
+pub struct IntentRef{
+    pub name: String,
+    #[serde(rename = "displayName", default)]
+    pub display_name: String,
+}
+
+/**
+* A rich response message. Text, image, quick replies, card and custom payload messages are typed;
+* other kinds of message, such as the Actions on Google ones, leave all the fields empty.
+*/
+#[derive(Clone,Default,Serialize,Deserialize)]
+pub struct FulfillmentMessage{
+    #[serde(skip_serializing_if="Option::is_none", default)]
+    pub text: Option<TextMessage>,
+    #[serde(skip_serializing_if="Option::is_none", default)]
+    pub image: Option<ImageMessage>,
+    #[serde(rename = "quickReplies", skip_serializing_if="Option::is_none", default)]
+    pub quick_replies: Option<QuickRepliesMessage>,
+    #[serde(skip_serializing_if="Option::is_none", default)]
+    pub card: Option<CardMessage>,
+    #[serde(skip_serializing_if="Option::is_none", default)]
+    pub payload: Option<Map<String, Value>>,
+    /**
+    * The platform the message is for, e.g. `FACEBOOK` or `ACTIONS_ON_GOOGLE`
+    */
+    #[serde(skip_serializing_if="Option::is_none", default)]
+    pub platform: Option<String>,
+}
+
+#[derive(Clone,Serialize,Deserialize)]
+pub struct TextMessage{
+    #[serde(default)]
+    pub text: Vec<String>,
+}
+
+#[derive(Clone,Serialize,Deserialize)]
+pub struct ImageMessage{
+    #[serde(rename = "imageUri", default)]
+    pub image_uri: String,
+}
+
+#[derive(Clone,Default,Serialize,Deserialize)]
+pub struct QuickRepliesMessage{
+    #[serde(default)]
+    pub title: String,
+    #[serde(rename = "quickReplies", default)]
+    pub quick_replies: Vec<String>,
+}
+
+#[derive(Clone,Default,Serialize,Deserialize)]
+pub struct CardMessage{
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub subtitle: String,
+    #[serde(rename = "imageUri", default)]
+    pub image_uri: String,
+    #[serde(default)]
+    pub buttons: Vec<CardMessageButton>,
+}
+
+#[derive(Clone,Default,Serialize,Deserialize)]
+pub struct CardMessageButton{
+    #[serde(default)]
+    pub text: String,
+    #[serde(default)]
+    pub postback: String,
+}
+
+/**
+* A Google API status, as reported for webhook calls and in error responses
+*
+*/
+#[derive(Clone,Serialize,Deserialize)]
+pub struct RpcStatus{
+    #[serde(default)]
+    pub code: i32,
+    #[serde(default)]
+    pub message: String,
+    #[serde(skip_serializing_if="Option::is_none", default)]
+    pub status: Option<String>,
+}
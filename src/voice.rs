@@ -0,0 +1,109 @@
+use uuid::Uuid;
+
+use transport::StreamingBody;
+
+use std::fs::File;
+use std::io::{self, Cursor, Read};
+use std::path::Path;
+
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/**
+* Recorded speech to send with a voice query.
+*
+* Audio read from a file is sent with a Content-Length; audio from any other reader is streamed with
+* chunked transfer encoding unless its length is given.
+*/
+pub struct AudioSource {
+    reader: Box<Read + Send>,
+    length: Option<u64>,
+}
+
+impl AudioSource {
+
+    pub fn from_reader<R: Read + Send + 'static>(reader: R) -> AudioSource {
+        AudioSource{ reader: Box::new(reader), length: Option::None }
+    }
+
+    /**
+    * Audio of a known length in bytes
+    */
+    pub fn from_reader_with_length<R: Read + Send + 'static>(reader: R, length: u64) -> AudioSource {
+        AudioSource{ reader: Box::new(reader), length: Option::Some(length) }
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<AudioSource> {
+        let file = File::open(path)?;
+        let length = file.metadata()?.len();
+
+        Result::Ok(AudioSource::from_reader_with_length(file, length))
+    }
+}
+
+/**
+* The encoding of the audio in a voice query. API.ai expects 16 kHz mono speech.
+*
+*/
+#[derive(Clone,Debug,PartialEq)]
+pub enum AudioContentType {
+    /**
+    * A WAV file holding 16 bit, 16 kHz mono PCM
+    */
+    Wav,
+    /**
+    * Raw 16 bit, 16 kHz mono PCM without a header
+    */
+    Pcm16k,
+    Custom(String),
+}
+
+impl AudioContentType {
+
+    pub fn mime_type(&self) -> &str {
+        match *self {
+            AudioContentType::Wav => "audio/wav",
+            AudioContentType::Pcm16k => "audio/L16; rate=16000; channels=1",
+            AudioContentType::Custom(ref mime_type) => mime_type.as_str(),
+        }
+    }
+
+    fn file_name(&self) -> &'static str {
+        match *self {
+            AudioContentType::Wav => "voice.wav",
+            _ => "voice.raw",
+        }
+    }
+}
+
+/**
+* Build the multipart/form-data body for a voice query: the JSON request in a `request` part
+* followed by the audio in a `voiceData` part.
+*
+* Returns the Content-Type header, including the boundary, along with the body. The audio is read
+* as the body is sent rather than up front.
+*/
+pub(crate) fn multipart_body(request: Vec<u8>, audio: AudioSource, content_type: &AudioContentType) -> (String, StreamingBody) {
+
+    let boundary = format!("apiai-{}", Uuid::new_v4().simple());
+
+    let mut head = Vec::new();
+    head.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+    head.extend_from_slice(b"Content-Disposition: form-data; name=\"request\"\r\n");
+    head.extend_from_slice(b"Content-Type: application/json\r\n\r\n");
+    head.extend_from_slice(&request);
+    head.extend_from_slice(format!("\r\n--{}\r\n", boundary).as_bytes());
+    head.extend_from_slice(format!("Content-Disposition: form-data; name=\"voiceData\"; filename=\"{}\"\r\n",
+                                   content_type.file_name()).as_bytes());
+    head.extend_from_slice(format!("Content-Type: {}\r\n\r\n", content_type.mime_type()).as_bytes());
+
+    let tail = format!("\r\n--{}--\r\n", boundary).into_bytes();
+
+    let length = audio.length.map(|length| head.len() as u64 + length + tail.len() as u64);
+    let reader = Cursor::new(head).chain(audio.reader).chain(Cursor::new(tail));
+
+    (format!("multipart/form-data; boundary={}", boundary), StreamingBody{
+        reader: Box::new(reader),
+        length: length,
+    })
+}